@@ -1,12 +1,23 @@
 use core::panic;
-use syn::{Data, DeriveInput, Fields, Ident, Meta, NestedMeta};
+use syn::{
+    Attribute, Data, DeriveInput, Expr, Fields, Ident, Lit, LitInt, Meta, NestedMeta, Path, Token,
+    Type, parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+};
 
-use crate::derive_database::Field;
+use crate::{
+    derive_database::{Compare, Derived, Element, Field, Indexed, Subset},
+    derive_subset::{Deadband, Superset},
+};
 
-pub(crate) fn extract_database_attributes(input: &DeriveInput) -> (Ident, Vec<Ident>) {
+pub(crate) fn extract_database_attributes(
+    input: &DeriveInput,
+) -> (Option<Ident>, Option<Ident>, Vec<Subset>) {
     // Parse attributes
     let mut database_name: Option<Ident> = None;
-    let mut subsets: Vec<Ident> = Vec::new();
+    let mut module: Option<Ident> = None;
+    let mut subsets: Vec<Subset> = Vec::new();
 
     for attr in &input.attrs {
         if attr.path.is_ident("name") {
@@ -15,36 +26,49 @@ pub(crate) fn extract_database_attributes(input: &DeriveInput) -> (Ident, Vec<Id
                     database_name = path.get_ident().cloned();
                 }
             }
+        } else if attr.path.is_ident("module") {
+            // Emits the generated items under a module, such as #[module(my_db)]
+            match attr.parse_args::<Ident>() {
+                Ok(ident) => module = Some(ident),
+                Err(error) => panic!("invalid module attribute: {}", error),
+            }
         } else if attr.path.is_ident("subset") {
-            if let Ok(Meta::List(meta_list)) = attr.parse_meta() {
-                if let Some(NestedMeta::Meta(Meta::Path(path))) = meta_list.nested.first() {
-                    subsets.push(path.get_ident().unwrap().clone());
-                }
+            // Subsets of generic databases are supplied along with their generics, such as
+            // #[subset(MySubset<T>)], and may be followed by their static subscribers
+            match attr.parse_args::<Subset>() {
+                Ok(subset) => subsets.push(subset),
+                Err(error) => panic!("invalid subset attribute: {}", error),
             }
         }
     }
 
-    // Default name fallback if #[name(...)] not provided
-    let database_name = match database_name {
-        None => {
-            panic!("name for the resulting database structure needs to be supplied");
-        }
-        Some(name) => name,
-    };
+    (database_name, module, subsets)
+}
+
+// Content structs marked with `#[group]` are groups, which are only flattened into other content
+// structs
+pub(crate) fn extract_group_attribute(input: &DeriveInput) -> bool {
+    input.attrs.iter().any(|attr| attr.path.is_ident("group"))
+}
 
-    (database_name, subsets)
+// Members of the database are `Copy` unless the content struct is marked with `#[no_copy]`
+pub(crate) fn extract_copy_attribute(input: &DeriveInput) -> bool {
+    !input.attrs.iter().any(|attr| attr.path.is_ident("no_copy"))
 }
 
-pub(crate) fn extract_subset_attributes(input: &DeriveInput) -> Ident {
+pub(crate) fn extract_subset_attributes(input: &DeriveInput) -> Superset {
     // Parse attributes
-    let mut superset: Option<Ident> = None;
+    let mut superset: Option<Superset> = None;
     for attr in &input.attrs {
         if attr.path.is_ident("superset") {
-            if let Ok(Meta::List(meta_list)) = attr.parse_meta() {
-                if let Some(NestedMeta::Meta(Meta::Path(path))) = meta_list.nested.first() {
-                    superset = path.get_ident().cloned();
+            // The superset can be supplied as a full path, such as #[superset(crate::db::MyDatabase)],
+            // or as the module of a database, such as #[superset(module = crate::db::my_db)]
+            match attr.parse_args::<Superset>() {
+                Ok(parsed) => {
+                    superset = Some(parsed);
                     break;
                 }
+                Err(error) => panic!("invalid superset attribute: {}", error),
             }
         }
     }
@@ -55,6 +79,37 @@ pub(crate) fn extract_subset_attributes(input: &DeriveInput) -> Ident {
     }
 }
 
+pub(crate) fn extract_deadband_attribute(input: &DeriveInput) -> Vec<Deadband> {
+    // Expects a list of field thresholds, such as #[deadband(alice = 10, bob = 2)]
+    let mut deadbands: Vec<Deadband> = Vec::new();
+    for attr in &input.attrs {
+        if attr.path.is_ident("deadband") {
+            match attr.parse_meta() {
+                Ok(Meta::List(meta_list)) => {
+                    for nested in meta_list.nested.iter() {
+                        match nested {
+                            NestedMeta::Meta(Meta::NameValue(name_value)) => {
+                                deadbands.push(Deadband {
+                                    field_name: name_value
+                                        .path
+                                        .get_ident()
+                                        .expect("deadband needs to be supplied a field name")
+                                        .clone(),
+                                    threshold: name_value.lit.clone(),
+                                });
+                            }
+                            _ => panic!("deadband needs to be on the form `field = threshold`"),
+                        }
+                    }
+                }
+                _ => panic!("deadband needs to be on the form `#[deadband(field = threshold)]`"),
+            }
+        }
+    }
+
+    deadbands
+}
+
 pub(crate) fn extract_fields(input: &DeriveInput) -> Vec<Field> {
     // Extract the fields from the input
     let fields = match input.data {
@@ -68,12 +123,217 @@ pub(crate) fn extract_fields(input: &DeriveInput) -> Vec<Field> {
         for field in &named_fields.named {
             let field_name = field.ident.as_ref().unwrap();
             let field_type = &field.ty;
-            field_info.push(Field {
+            let field = Field {
                 field_name,
                 field_type,
-            });
+                derived: extract_derived_attribute(&field.attrs),
+                history: extract_history_attribute(&field.attrs),
+                path: extract_path_attribute(&field.attrs)
+                    .unwrap_or_else(|| vec![field_name.clone()]),
+                indexed: extract_indexed_attribute(&field.attrs, field_type),
+                element: extract_element_attribute(&field.attrs),
+                compare: extract_compare_attribute(&field.attrs),
+                subscribable: field
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path.is_ident("subscribable")),
+            };
+
+            if field.indexed.is_some() && (field.history.is_some() || field.derived.is_some()) {
+                panic!(
+                    "indexed member `{}` can't have a history or be derived",
+                    field_name
+                );
+            }
+
+            field_info.push(field);
         }
     }
 
     field_info
 }
+
+impl Parse for Superset {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Ident) && input.peek2(Token![=]) {
+            let key: Ident = input.parse()?;
+            if key != "module" {
+                return Err(syn::Error::new(key.span(), "expected `module`"));
+            }
+            input.parse::<Token![=]>()?;
+            Ok(Superset::Module(input.parse()?))
+        } else {
+            Ok(Superset::Database(input.parse()?))
+        }
+    }
+}
+
+// Parses the arguments of a subset attribute, on the form `MySubset, subscribers(a, b), group = fast`
+impl Parse for Subset {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path: Path = input.parse()?;
+        let mut subscribers = Vec::new();
+        let mut group: Option<Ident> = None;
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            let key: Ident = input.parse()?;
+            if key == "subscribers" {
+                let content;
+                parenthesized!(content in input);
+                let paths = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                subscribers = paths.into_iter().collect();
+            } else if key == "group" {
+                input.parse::<Token![=]>()?;
+                group = Some(input.parse()?);
+            } else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "expected `subscribers` or `group`",
+                ));
+            }
+        }
+
+        Ok(Subset {
+            path,
+            subscribers,
+            group,
+        })
+    }
+}
+
+// Parses the arguments of a derived attribute, on the form `inputs(alice, bob), with = function`
+impl Parse for Derived {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut inputs: Option<Vec<Ident>> = None;
+        let mut function: Option<Path> = None;
+
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            if key == "inputs" {
+                let content;
+                parenthesized!(content in input);
+                let idents = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+                inputs = Some(idents.into_iter().collect());
+            } else if key == "with" {
+                input.parse::<Token![=]>()?;
+                function = Some(input.parse()?);
+            } else {
+                return Err(syn::Error::new(key.span(), "expected `inputs` or `with`"));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        match (inputs, function) {
+            (Some(inputs), Some(function)) => Ok(Derived { inputs, function }),
+            _ => {
+                Err(input.error("derived needs to be supplied both `inputs(...)` and `with = ...`"))
+            }
+        }
+    }
+}
+
+fn extract_derived_attribute(attrs: &[Attribute]) -> Option<Derived> {
+    attrs
+        .iter()
+        .find(|attr| attr.path.is_ident("derived"))
+        .map(|attr| match attr.parse_args::<Derived>() {
+            Ok(derived) => derived,
+            Err(error) => panic!("invalid derived attribute: {}", error),
+        })
+}
+
+fn extract_history_attribute(attrs: &[Attribute]) -> Option<LitInt> {
+    attrs
+        .iter()
+        .find(|attr| attr.path.is_ident("history"))
+        .map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(meta_list)) => match meta_list.nested.first() {
+                Some(NestedMeta::Lit(Lit::Int(size))) => size.clone(),
+                _ => panic!("history needs to be supplied a size, such as `#[history(32)]`"),
+            },
+            _ => panic!("history needs to be on the form `#[history(size)]`"),
+        })
+}
+
+// Flattened members carry the path to their value in the content struct, such as
+// `#[database_path(motor, speed)]`
+fn extract_path_attribute(attrs: &[Attribute]) -> Option<Vec<Ident>> {
+    attrs
+        .iter()
+        .find(|attr| attr.path.is_ident("database_path"))
+        .map(
+            |attr| match attr.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated) {
+                Ok(path) => path.into_iter().collect(),
+                Err(error) => panic!("invalid path attribute: {}", error),
+            },
+        )
+}
+
+fn extract_indexed_attribute(attrs: &[Attribute], field_type: &Type) -> Option<Indexed> {
+    if !attrs.iter().any(|attr| attr.path.is_ident("indexed")) {
+        return None;
+    }
+
+    match field_type {
+        Type::Array(array) => Some(Indexed {
+            element_type: (*array.elem).clone(),
+            length: array.len.clone(),
+        }),
+        _ => panic!("indexed members needs to be arrays, such as `[u16; 8]`"),
+    }
+}
+
+// Parses an element of an indexed member, on the form `#[element(gains, 3)]`
+fn extract_element_attribute(attrs: &[Attribute]) -> Option<Element> {
+    attrs
+        .iter()
+        .find(|attr| attr.path.is_ident("element"))
+        .map(|attr| {
+            let arguments =
+                match attr.parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+                    Ok(arguments) => arguments,
+                    Err(error) => panic!("invalid element attribute: {}", error),
+                };
+
+            match (arguments.first(), arguments.iter().nth(1), arguments.len()) {
+                (Some(Expr::Path(member)), Some(index), 2) if member.path.get_ident().is_some() => {
+                    Element {
+                        member: member.path.get_ident().unwrap().clone(),
+                        index: index.clone(),
+                    }
+                }
+                _ => panic!("element needs to be on the form `#[element(member, index)]`"),
+            }
+        })
+}
+
+// Parses the comparison of a member, on the form `#[compare(epsilon = 0.01)]` or
+// `#[compare(nan_aware)]`
+fn extract_compare_attribute(attrs: &[Attribute]) -> Option<Compare> {
+    attrs
+        .iter()
+        .find(|attr| attr.path.is_ident("compare"))
+        .map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(meta_list)) => match meta_list.nested.first() {
+                Some(NestedMeta::Meta(Meta::NameValue(name_value)))
+                    if name_value.path.is_ident("epsilon") =>
+                {
+                    Compare::Epsilon(name_value.lit.clone())
+                }
+                Some(NestedMeta::Meta(Meta::Path(path))) if path.is_ident("nan_aware") => {
+                    Compare::NanAware
+                }
+                Some(NestedMeta::Meta(Meta::Path(path))) if path.is_ident("bitwise") => {
+                    Compare::Bitwise
+                }
+                _ => {
+                    panic!("compare needs to be supplied `epsilon = ...`, `nan_aware` or `bitwise`")
+                }
+            },
+            _ => panic!("compare needs to be on the form `#[compare(...)]`"),
+        })
+}
@@ -1,8 +1,12 @@
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::Ident;
+use syn::{Generics, Ident, Type};
 
-use crate::{derive_database::Field, dromedar_case::to_dromedar_case};
+use crate::{
+    derive_database::{Compare, Field},
+    dromedar_case::to_dromedar_case,
+    enum_impl::generate_member_offsets,
+};
 
 pub(crate) fn generate_database_content_impl(
     crate_path: &TokenStream2,
@@ -10,15 +14,23 @@ pub(crate) fn generate_database_content_impl(
     enum_name: &Ident,
     enum_size: &Ident,
     fields: &[Field],
+    generics: &Generics,
 ) -> TokenStream2 {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
     // Generate match arms for `set`
     let set_arms = fields.iter().map(|field| {
         let variant_name_str = to_dromedar_case(&field.field_name.to_string());
         let variant_ident = Ident::new(&variant_name_str, field.field_name.span());
-        let field_name = &field.field_name;
+        let access = field.access();
 
-        quote! {
-            #enum_name::#variant_ident(value) => self.#field_name = value,
+        match field.indexed {
+            Some(_) => quote! {
+                #enum_name::#variant_ident(index, value) => self.#access[index] = value,
+            },
+            None => quote! {
+                #enum_name::#variant_ident(value) => self.#access = value,
+            },
         }
     });
 
@@ -26,27 +38,249 @@ pub(crate) fn generate_database_content_impl(
     let get_arms = fields.iter().map(|field| {
         let variant_name_str = to_dromedar_case(&field.field_name.to_string());
         let variant_ident = Ident::new(&variant_name_str, field.field_name.span());
-        let field_name = &field.field_name;
+        let access = field.access();
 
-        quote! {
-            #enum_name::#variant_ident(_) => #enum_name::#variant_ident(self.#field_name),
+        match field.indexed {
+            Some(_) => quote! {
+                #enum_name::#variant_ident(index, _) => #enum_name::#variant_ident(*index, self.#access[*index].clone()),
+            },
+            None => quote! {
+                #enum_name::#variant_ident(_) => #enum_name::#variant_ident(self.#access.clone()),
+            },
         }
     });
 
+    // Generate a comparison of each member to its previous value. Members without a
+    // `#[compare(...)]` attribute are compared bitwise if they're `f32` or `f64`, and with `!=`
+    // otherwise. Floats compared bitwise notifies a change between `0.0` and `-0.0` but not between
+    // two identical NaNs
+    //
+    // Expands to the following
+    //
+    // fn is_changed(previous: &MyDatabaseMember, current: &MyDatabaseMember) -> bool {
+    //     match (previous, current) {
+    //         (MyDatabaseMember::Alice(previous), MyDatabaseMember::Alice(current)) => previous != current,
+    //         (MyDatabaseMember::Temperature(previous), MyDatabaseMember::Temperature(current)) => {
+    //             !database::Float::bitwise_eq(*previous, *current)
+    //         }
+    //         _ => true,
+    //     }
+    // }
+    let compare_arms: TokenStream2 = fields
+        .iter()
+        .map(|field| {
+            let variant_name_str = to_dromedar_case(&field.field_name.to_string());
+            let variant_ident = Ident::new(&variant_name_str, field.field_name.span());
+
+            let comparison = match &field.compare {
+                Some(Compare::Epsilon(epsilon)) => quote! {
+                    #crate_path::exceeds_deadband(*current, *previous, #epsilon)
+                },
+                Some(Compare::NanAware) => quote! {
+                    !(previous == current
+                        || (#crate_path::Float::is_nan(*previous) && #crate_path::Float::is_nan(*current)))
+                },
+                Some(Compare::Bitwise) => quote! {
+                    !#crate_path::Float::bitwise_eq(*previous, *current)
+                },
+                None => {
+                    let ty = match &field.indexed {
+                        Some(indexed) => &indexed.element_type,
+                        None => field.field_type,
+                    };
+                    if is_float(ty) {
+                        quote! { !#crate_path::Float::bitwise_eq(*previous, *current) }
+                    } else {
+                        quote! { previous != current }
+                    }
+                }
+            };
+
+            match field.indexed {
+                Some(_) => quote! {
+                    (#enum_name::#variant_ident(_, previous), #enum_name::#variant_ident(_, current)) => #comparison,
+                },
+                None => quote! {
+                    (#enum_name::#variant_ident(previous), #enum_name::#variant_ident(current)) => #comparison,
+                },
+            }
+        })
+        .collect();
+
+    let is_changed = quote! {
+        fn is_changed(previous: &#enum_name #ty_generics, current: &#enum_name #ty_generics) -> bool {
+            match (previous, current) {
+                #compare_arms
+                _ => true,
+            }
+        }
+    };
+
+    // Generate a check for whether a parameter is derived, if any derived parameters exist
+    //
+    // Expands to the following
+    //
+    // fn is_derived(parameter: &MyDatabaseMember) -> bool {
+    //     matches!(parameter, MyDatabaseMember::Power(_))
+    // }
+    let derived_variants: Vec<Ident> = fields
+        .iter()
+        .filter(|field| field.derived.is_some())
+        .map(|field| {
+            let variant_name_str = to_dromedar_case(&field.field_name.to_string());
+            Ident::new(&variant_name_str, field.field_name.span())
+        })
+        .collect();
+
+    let is_derived = if derived_variants.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn is_derived(parameter: &#enum_name #ty_generics) -> bool {
+                matches!(parameter, #(#enum_name::#derived_variants(..))|*)
+            }
+        }
+    };
+
+    // Generate a recomputation of each derived parameter whose inputs has changed. Derived
+    // parameters are recomputed in declaration order, which allows them to depend on derived
+    // parameters declared before them
+    //
+    // Expands to the following
+    //
+    // if changed[0] || changed[1] {
+    //     let previous = MyDatabaseMember::Power(self.power.clone());
+    //     self.power = compute_power(self.voltage.clone(), self.current.clone());
+    //     let current = MyDatabaseMember::Power(self.power.clone());
+    //     if <Self as database::DatabaseContent>::is_changed(&previous, &current) {
+    //         changed[2] = true;
+    //         on_update(previous, current);
+    //     }
+    // }
+    let (offsets, _) = generate_member_offsets(fields);
+    let field_index = |name: &Ident| fields.iter().position(|field| field.field_name == name);
+    let derived_updates: TokenStream2 = fields
+        .iter()
+        .enumerate()
+        .filter_map(|(index, field)| field.derived.as_ref().map(|derived| (index, field, derived)))
+        .map(|(index, field, derived)| {
+            let field_name = field.field_name;
+            let variant_name_str = to_dromedar_case(&field_name.to_string());
+            let variant_ident = Ident::new(&variant_name_str, field_name.span());
+            let access = field.access();
+            let function = &derived.function;
+            let inputs = &derived.inputs;
+
+            let input_indices: Vec<usize> = inputs
+                .iter()
+                .map(|input| match field_index(input) {
+                    Some(input_index) if input_index == index => {
+                        panic!("derived parameter `{}` can't be its own input", field_name)
+                    }
+                    Some(input_index)
+                        if input_index > index && fields[input_index].derived.is_some() =>
+                    {
+                        panic!(
+                            "derived parameter `{}` needs to be declared after its derived input `{}`",
+                            field_name, input
+                        )
+                    }
+                    Some(input_index) => input_index,
+                    None => panic!(
+                        "input `{}` of derived parameter `{}` is not a member of the database",
+                        input, field_name
+                    ),
+                })
+                .collect();
+            let input_access: Vec<TokenStream2> = input_indices
+                .iter()
+                .map(|input_index| fields[*input_index].access())
+                .collect();
+
+            // Indexed inputs have changed if any of their elements have changed
+            let input_changed: Vec<TokenStream2> = input_indices
+                .iter()
+                .map(|input_index| {
+                    let offset = &offsets[*input_index];
+                    match &fields[*input_index].indexed {
+                        Some(indexed) => {
+                            let length = &indexed.length;
+                            quote! { changed[#offset..#offset + #length].iter().any(|changed| *changed) }
+                        }
+                        None => quote! { changed[#offset] },
+                    }
+                })
+                .collect();
+            let offset = &offsets[index];
+
+            quote! {
+                if false #(|| #input_changed)* {
+                    let previous = #enum_name::#variant_ident(self.#access.clone());
+                    self.#access = #function(#(self.#input_access.clone()),*);
+                    let current = #enum_name::#variant_ident(self.#access.clone());
+                    if <Self as #crate_path::DatabaseContent>::is_changed(&previous, &current) {
+                        changed[#offset] = true;
+                        on_update(previous, current);
+                    }
+                }
+            }
+        })
+        .collect();
+
+    let update_derived = if derived_variants.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn update_derived(
+                &mut self,
+                changed: &mut [bool],
+                on_update: &mut dyn FnMut(#enum_name #ty_generics, #enum_name #ty_generics),
+            ) {
+                #derived_updates
+            }
+        }
+    };
+
     // Build full impl
     quote! {
-        impl #crate_path::DatabaseContent<#enum_name, #enum_size> for #struct_name {
-            fn set(&mut self, parameter: #enum_name) {
+        impl #impl_generics #crate_path::DatabaseContent for #struct_name #ty_generics #where_clause {
+            type Member = #enum_name #ty_generics;
+
+            const COUNT: usize = #enum_size;
+
+            fn set(&mut self, parameter: #enum_name #ty_generics) {
                 match parameter {
                     #(#set_arms)*
                 }
             }
 
-            fn get(&self, parameter: &#enum_name) -> #enum_name {
+            fn get(&self, parameter: &#enum_name #ty_generics) -> #enum_name #ty_generics {
                 match parameter {
                     #(#get_arms)*
                 }
             }
+
+            #is_changed
+
+            #is_derived
+
+            #update_derived
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #crate_path::DatabaseMember for #enum_name #ty_generics #where_clause {
+            type Content = #struct_name #ty_generics;
+        }
+    }
+}
+
+// Floats are recognized by their type name, as aliases and generics can't be resolved by the macro.
+// Those are compared with `!=` unless they have a `#[compare(...)]` attribute
+fn is_float(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => {
+            type_path.path.is_ident("f32") || type_path.path.is_ident("f64")
         }
+        _ => false,
     }
 }
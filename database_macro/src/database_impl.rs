@@ -1,46 +1,293 @@
 use proc_macro2::Span;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::Ident;
+use syn::{Generics, Ident};
 
+use crate::{
+    derive_database::{Field, Subset, with_database_lifetime},
+    dromedar_case::to_dromedar_case,
+    enum_impl::generate_member_offsets,
+    subscriber_handler_impl::subset_name,
+};
+
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn generate_database_impl(
     crate_path: &TokenStream2,
     database_name: &Ident,
     struct_name: &Ident,
+    subscriber_handler_ident: &Ident,
+    history_ident: &Ident,
     enum_name: &Ident,
     enum_size: &Ident,
+    key_name: &Ident,
+    group_names: Option<&(Ident, Ident)>,
+    subsets: &[Subset],
+    fields: &[Field],
+    generics: &Generics,
 ) -> TokenStream2 {
-    let subscriber_handler_ident = Ident::new(
-        &format!("{}SubscriberHandler", struct_name),
-        Span::call_site(),
-    );
+    // The database borrows its subscribers and undo journal, and takes the generics of the content
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let database_generics = with_database_lifetime(generics);
+    let (impl_generics, database_ty_generics, where_clause) = database_generics.split_for_impl();
+
+    // Generates accessors for the history of each member with a `#[history(...)]` attribute. The
+    // history is borrowed, as its values aren't necessarily `Copy`. Expands to the following
+    //
+    // pub fn with_history_of_bob<R>(
+    //     &self,
+    //     function: impl FnOnce(&database::RingBuffer<u16, 32>) -> R,
+    // ) -> R {
+    //     self.0.with_history(|history| function(&history.bob))
+    // }
+    //
+    // pub fn clear_history_of_bob(&self) {
+    //     self.0.with_history(|history| history.bob.clear())
+    // }
+    let history_functions: TokenStream2 = fields
+        .iter()
+        .filter(|field| field.history.is_some())
+        .map(|field| {
+            let field_name = field.field_name;
+            let ty = field.field_type;
+            let size = &field.history;
+            let history_function = Ident::new(
+                &format!("with_history_of_{}", field_name),
+                Span::call_site(),
+            );
+            let clear_function = Ident::new(
+                &format!("clear_history_of_{}", field_name),
+                Span::call_site(),
+            );
+
+            quote! {
+                /// Borrow the recorded history of the parameter, which iterates over sequence numbers,
+                /// timestamps and values from the oldest to the newest. Values are only timestamped if
+                /// the database has a clock. The database is locked in a critical section until
+                /// `function` returns
+                pub fn #history_function<R>(
+                    &self,
+                    function: impl FnOnce(&#crate_path::RingBuffer<#ty, #size>) -> R,
+                ) -> R {
+                    self.0.with_history(|history| function(&history.#field_name))
+                }
+
+                /// Clear the recorded history of the parameter
+                pub fn #clear_function(&self) {
+                    self.0.with_history(|history| history.#field_name.clear())
+                }
+            }
+        })
+        .collect();
+
+    // Generates the range of each key in the parameter change list, as indexed members take up one
+    // index per element. Expands to the following
+    //
+    // (MyDatabaseKey::Alice, 0, 1),
+    // (MyDatabaseKey::Gains, 1, 4),
+    let (offsets, _) = generate_member_offsets(fields);
+    let key_ranges: TokenStream2 = fields
+        .iter()
+        .zip(offsets.iter())
+        .map(|(field, offset)| {
+            let variant = Ident::new(
+                &to_dromedar_case(&field.field_name.to_string()),
+                field.field_name.span(),
+            );
+            let length = match &field.indexed {
+                Some(indexed) => {
+                    let length = &indexed.length;
+                    quote! { #length }
+                }
+                None => quote! { 1 },
+            };
+
+            quote! { (#key_name::#variant, #offset, #length), }
+        })
+        .collect();
+
+    // Generates a notify function for the groups of the subsets, along with an accessor for the
+    // handler of the groups, if there are any
+    let group_handler = match group_names {
+        Some((_, group_handler_ident)) => quote! { #group_handler_ident #database_ty_generics },
+        None => quote! { () },
+    };
+    let group_functions = group_names.map(|(group_name, group_handler_ident)| {
+        quote! {
+            /// Notify the subscribers of a group of subsets of all parameters changed since the group was
+            /// last notified. Groups keep track of their changes separately from each other and from
+            /// `notify_subscribers`, such that they can be notified from different contexts. Each group
+            /// has a lock of its own, such that a group can be notified while another context is notifying
+            /// the subscriber handler or any other group. If the group is locked, this will cause a
+            /// `DatabaseError` and the changes are kept until the group is notified again
+            pub fn notify_group(&self, group: #group_name) -> Result<(), #crate_path::DatabaseError> {
+                self.0.notify_group(group as usize)
+            }
+
+            /// Retrieve the handler of the subsets in groups. Each group is locked separately, such that
+            /// subscribers can be added to a group while another group is being notified
+            pub fn get_group_handler(&'a self) -> &'a #group_handler_ident #database_ty_generics {
+                self.0.get_group_handler()
+            }
+        }
+    });
+
+    // Generates a function subscribing to each subset with its current value, which is built from
+    // the database while the handler of the subset is locked, such that no change is missed in
+    // between. Expands to the following
+    //
+    // pub fn subscribe_with_my_subset1_and_sync(
+    //     &self,
+    //     subscriber: &'a dyn database::DatabaseSubscriber<MySubset1>,
+    // ) -> Result<(), database::DatabaseError> {
+    //     self.0.with_subscriber_handler(|handler, database| {
+    //         handler.subscribe_with_my_subset1_and_sync(subscriber, database)
+    //     })?
+    // }
+    let sync_functions: TokenStream2 = subsets
+        .iter()
+        .map(|subset| {
+            let path = &subset.path;
+            let sync_function = Ident::new(
+                &format!("subscribe_with_{}_and_sync", subset_name(subset)),
+                Span::call_site(),
+            );
+            let subscribe = match subset.group {
+                Some(_) => quote! {
+                    self.0.get_group_handler().#sync_function(subscriber, &self.0)
+                },
+                None => quote! {
+                    self.0.with_subscriber_handler(|handler, database| {
+                        handler.#sync_function(subscriber, database)
+                    })?
+                },
+            };
+
+            quote! {
+                /// Subscribe to the subset and notify the subscriber of its current value right away,
+                /// instead of waiting for the next change. If the subscribers of the subset are locked,
+                /// this will cause a `DatabaseError`
+                pub fn #sync_function(
+                    &self,
+                    subscriber: &'a dyn #crate_path::DatabaseSubscriber<#path>,
+                ) -> Result<(), #crate_path::DatabaseError> {
+                    #subscribe
+                }
+            }
+        })
+        .collect();
+
+    // Generates borrow-based accessors for each member, which avoids copying large values. Expands
+    // to the following
+    //
+    // pub fn with_name<R>(&self, function: impl FnOnce(&DeviceName) -> R) -> R {
+    //     self.0.with_content(|content| function(&content.name))
+    // }
+    let with_functions: TokenStream2 = fields
+        .iter()
+        .map(|field| {
+            let ty = field.field_type;
+            let access = field.access();
+            let with_function =
+                Ident::new(&format!("with_{}", field.field_name), Span::call_site());
+
+            quote! {
+                /// Borrow the value of the parameter without copying it. The database is locked in
+                /// a critical section until `function` returns
+                pub fn #with_function<R>(&self, function: impl FnOnce(&#ty) -> R) -> R {
+                    self.0.with_content(|content| function(&content.#access))
+                }
+            }
+        })
+        .collect();
 
     // Build full impl
     quote! {
-        pub struct #database_name<'a>(#crate_path::DatabaseHandler<'a, #struct_name, #subscriber_handler_ident<'a>, #enum_name, #enum_size>);
+        pub struct #database_name #database_generics (
+            #crate_path::DatabaseHandler<
+                'a,
+                #struct_name #ty_generics,
+                #subscriber_handler_ident #database_ty_generics,
+                #enum_name #ty_generics,
+                #enum_size,
+                #history_ident #ty_generics,
+                #group_handler,
+            >,
+        ) #where_clause;
 
-        impl<'a> #database_name<'a> {
-            pub fn new(content: #struct_name) -> Self {
+        impl #impl_generics #database_name #database_ty_generics #where_clause {
+            /// Create a new database of the content. Derived parameters are computed from their inputs
+            /// before the database is created, such that they are consistent from the start
+            pub fn new(mut content: #struct_name #ty_generics) -> Self {
+                let mut changed = [true; #enum_size];
+                #crate_path::DatabaseContent::update_derived(&mut content, &mut changed, &mut |_, _| {});
                 Self(#crate_path::DatabaseHandler::new(content, #subscriber_handler_ident::new()))
             }
 
+            /// Record all set operations in an undo journal, which enables them to be undone and redone.
+            /// The depth of the journal is decided by the journal itself
+            pub fn with_undo_journal(
+                self,
+                undo_journal: &'a dyn #crate_path::UndoLog<#enum_name #ty_generics, #enum_size>,
+            ) -> Self {
+                Self(self.0.with_undo_journal(undo_journal))
+            }
+
+            /// Append every parameter set, along with the sequence number of its set operation, to
+            /// a change journal. Unlike the parameter change list, this keeps parameters that are
+            /// set again before subscribers are notified
+            pub fn with_change_journal(
+                self,
+                change_journal: &'a dyn #crate_path::ChangeLog<#enum_name #ty_generics>,
+            ) -> Self {
+                Self(self.0.with_change_journal(change_journal))
+            }
+
+            /// Signal when the database has changes waiting to be notified to subscribers, such that the
+            /// notifying context can be woken instead of polling the database
+            pub fn with_change_signal(self, change_signal: &'a dyn #crate_path::ChangeSignal) -> Self {
+                Self(self.0.with_change_signal(change_signal))
+            }
+
+            /// Stamp changes with the time they are made, such that notify policies are measured from
+            /// the change instead of from the next poll
+            pub fn with_clock(self, clock: &'a dyn #crate_path::Clock) -> Self {
+                Self(self.0.with_clock(clock))
+            }
+
             /// Retrieve a value from the database
-            pub fn get(&self, parameter: &#enum_name) -> #enum_name {
+            pub fn get(&self, parameter: &#enum_name #ty_generics) -> #enum_name #ty_generics {
                 self.0.get(parameter)
             }
 
             /// Set an array of parameters in a database. This will store a changed state for the provided
-            /// parameters, which later is acted upon by calling the `notify_subscribers` function
-            pub fn multi_set(&self, parameters: &[#enum_name]) {
+            /// parameters, which later is acted upon by calling the `notify_subscribers` function. Derived
+            /// parameters depending on the provided parameters are recomputed in the same critical
+            /// section. Derived parameters can't be set directly, and will cause a `DatabaseError` without
+            /// setting any of the parameters
+            pub fn multi_set(&self, parameters: &[#enum_name #ty_generics]) -> Result<(), #crate_path::DatabaseError> {
                 self.0.multi_set(parameters)
             }
 
             /// Set a parameter in a database. This will store a changed state for the provided
-            /// parameter, which later is acted upon by calling the `notify_subscribers` function
-            pub fn set(&self, parameter: &#enum_name) {
+            /// parameter, which later is acted upon by calling the `notify_subscribers` function. Derived
+            /// parameters can't be set directly, and will cause a `DatabaseError`
+            pub fn set(&self, parameter: &#enum_name #ty_generics) -> Result<(), #crate_path::DatabaseError> {
                 self.0.set(parameter)
             }
 
+            /// Undo the latest set operation recorded in the undo journal, restoring the parameters to
+            /// their previous values. The restored values are notified to subscribers as any other set
+            /// operation. Returns `false` if there is nothing to undo or no undo journal is used
+            pub fn undo(&self) -> bool {
+                self.0.undo()
+            }
+
+            /// Redo the latest undone set operation, setting the parameters to the values of the operation
+            /// once again. Returns `false` if there is nothing to redo or no undo journal is used
+            pub fn redo(&self) -> bool {
+                self.0.redo()
+            }
+
             /// Notify all subscribers of changes made to the database. This is separated out from the set
             /// functionality, as these might need to run under different contexts/priority levels. This
             /// function presumes that no other entity is actively handling the list of internal
@@ -50,10 +297,86 @@ pub(crate) fn generate_database_impl(
                 self.0.notify_subscribers()
             }
 
-            /// Retrieve a handle to the internal subscriber handler. Used to subscribe to different
-            /// subsets of the parameter space. This should be done before actively using the database, as
-            /// this can cause locking errors resulting in a failure to notify subscribers
-            pub fn get_subscriber_handler(&'a self) -> &'a #crate_path::SpinMutex<#crate_path::RefCell<#subscriber_handler_ident>> {
+            /// Notify subscribers of changes made to the database, stopping after `max_calls` subscribers
+            /// has been notified. The next call resumes where the previous one stopped, and each change is
+            /// notified to each subscriber once. Changes made in between are notified after the ones
+            /// being notified, which keep the values they were started with. If there are changes to
+            /// notify and the internal subscribers are locked for any reason, this will cause a
+            /// `DatabaseError`
+            pub fn notify_subscribers_budgeted(
+                &self,
+                max_calls: usize,
+            ) -> Result<#crate_path::NotifyProgress, #crate_path::DatabaseError> {
+                self.0.notify_subscribers_budgeted(max_calls)
+            }
+
+            /// Notify all subscribers of changes made to the database, or defer the notification to the
+            /// context currently notifying subscribers. Unlike `notify_subscribers`, this never fails on a
+            /// locked subscriber handler, as the context holding the lock notifies the changes before
+            /// releasing it
+            pub fn notify_subscribers_or_defer(&self) -> #crate_path::NotifyOutcome {
+                self.0.notify_subscribers_or_defer()
+            }
+
+            #group_functions
+
+            /// Notify all rate limited and debounced subscribers that are pending and whose policy allows
+            /// it at the time `now`. This should be called periodically from the same context as
+            /// `notify_subscribers`, as it requires the same lock on the internal subscribers
+            pub fn poll<I: #crate_path::Instant>(&self, now: I) -> Result<(), #crate_path::DatabaseError> {
+                self.0.poll(now)
+            }
+
+            /// The version of the database, which is increased by every set operation that changes a
+            /// parameter. Used as a cursor by readers polling for changes with `changed_since`
+            pub fn version(&self) -> u64 {
+                self.0.version()
+            }
+
+            /// The keys of all parameters changed after `version`. The version of the database should be
+            /// read before the changes, as changes made in between are otherwise missed by the reader
+            pub fn changed_since(&self, version: u64) -> impl Iterator<Item = #key_name> {
+                let mut changed = [false; #enum_size];
+                for index in self.0.changed_since(version) {
+                    changed[index] = true;
+                }
+
+                [#key_ranges]
+                    .into_iter()
+                    .filter(move |(_, offset, length)| changed[*offset..*offset + *length].contains(&true))
+                    .map(|(key, _, _)| key)
+            }
+
+            /// Build a subset from the database if any of its members changed after `version`. Each
+            /// reader keeps a version of its own, such that readers are independent of each other and of
+            /// the subscribers
+            pub fn subset_changed_since<ParameterSubset>(&self, version: u64) -> Option<ParameterSubset>
+            where
+                ParameterSubset: #crate_path::Subset<Database = #struct_name #ty_generics>,
+            {
+                self.0.subset_changed_since(version)
+            }
+
+            /// Create a reader polling the database for changes to a subset, as an alternative to
+            /// subscribing to it. The reader only borrows the database for as long as it's used
+            pub fn reader<ParameterSubset>(&self) -> #crate_path::Reader<'_, ParameterSubset>
+            where
+                ParameterSubset: #crate_path::Subset<Database = #struct_name #ty_generics>,
+            {
+                self.0.reader()
+            }
+
+            #with_functions
+
+            #history_functions
+
+            #sync_functions
+
+            /// Lock the internal subscriber handler, spinning until it's available. Used to subscribe to
+            /// different subsets of the parameter space. This should be done before actively using the
+            /// database, as this can cause locking errors resulting in a failure to notify subscribers. A
+            /// notification deferred while the lock is held is made when the lock is dropped
+            pub fn get_subscriber_handler(&'a self) -> #crate_path::SubscriberHandlerGuard<'a, #subscriber_handler_ident #database_ty_generics> {
                 self.0.get_subscriber_handler()
             }
         }
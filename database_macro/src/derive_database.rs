@@ -1,31 +1,184 @@
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{DeriveInput, Ident, Type, parse_macro_input};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{ToTokens, quote};
+use syn::{
+    DeriveInput, Expr, Generics, Ident, Lit, LitInt, Path, Type, Visibility, parse_macro_input,
+};
 
 use crate::{
-    attributes::{extract_database_attributes, extract_fields},
+    attributes::{
+        extract_copy_attribute, extract_database_attributes, extract_fields,
+        extract_group_attribute,
+    },
     content_impl::generate_database_content_impl,
     database_impl::generate_database_impl,
-    enum_impl::{generate_enum_names, generate_parameters_enum},
+    enum_impl::{generate_enum_names, generate_key_name, generate_parameters_enum},
+    flatten::{generate_flatten_invocation, generate_group_macro},
     get_crate_path,
+    group_handler_impl::{generate_group_enum, generate_group_handler_impl, subset_groups},
+    history_impl::generate_history_impl,
     subscriber_handler_impl::generate_subscriber_handler_impl,
 };
 
 pub(crate) struct Field<'a> {
     pub(crate) field_name: &'a Ident,
     pub(crate) field_type: &'a Type,
+    pub(crate) derived: Option<Derived>,
+    pub(crate) history: Option<LitInt>,
+    pub(crate) path: Vec<Ident>,
+    pub(crate) indexed: Option<Indexed>,
+    pub(crate) element: Option<Element>,
+    pub(crate) compare: Option<Compare>,
+    pub(crate) subscribable: bool,
+}
+
+impl Field<'_> {
+    /// The access path of the field from the content struct, such as `motor.speed` for a field
+    /// flattened from a `motor` member
+    pub(crate) fn access(&self) -> TokenStream2 {
+        let path = &self.path;
+        quote! { #(#path).* }
+    }
+}
+
+/// An array member tracked per element, declared with `#[indexed]`
+pub(crate) struct Indexed {
+    pub(crate) element_type: Type,
+    pub(crate) length: Expr,
+}
+
+/// A single element of an indexed member in a subset, declared with `#[element(member, index)]`
+pub(crate) struct Element {
+    pub(crate) member: Ident,
+    pub(crate) index: Expr,
+}
+
+/// How a member is compared to its previous value, declared with `#[compare(epsilon = 0.01)]`,
+/// `#[compare(nan_aware)]` or `#[compare(bitwise)]`. Members without the attribute are compared
+/// with `!=`
+pub(crate) enum Compare {
+    Epsilon(Lit),
+    NanAware,
+    Bitwise,
+}
+
+/// A subset of the database, declared with `#[subset(MySubset)]`. Subscribers known at compile
+/// time are declared along with it, such as `#[subset(MySubset, subscribers(MY_SUBSCRIBER))]`, and
+/// are called directly instead of being registered. Subsets in a group, such as
+/// `#[subset(MySubset, group = fast)]`, are notified separately from all other subsets
+pub(crate) struct Subset {
+    pub(crate) path: Path,
+    pub(crate) subscribers: Vec<Path>,
+    pub(crate) group: Option<Ident>,
+}
+
+/// A parameter computed from other parameters, declared with
+/// `#[derived(inputs(alice, bob), with = function)]`
+pub(crate) struct Derived {
+    pub(crate) inputs: Vec<Ident>,
+    pub(crate) function: Path,
+}
+
+/// The generics of the content struct, prefixed with the `'a` lifetime of the subscribers and undo
+/// journal borrowed by the database. A content struct declaring `'a` itself shares the lifetime
+pub(crate) fn with_database_lifetime(generics: &Generics) -> Generics {
+    let mut generics = generics.clone();
+    if !generics
+        .lifetimes()
+        .any(|lifetime| lifetime.lifetime.ident == "a")
+    {
+        generics.params.insert(0, syn::parse_quote!('a));
+    }
+    generics
 }
 
 pub(crate) fn derive_database(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    TokenStream::from(expand_database(input))
+}
+
+pub(crate) fn expand_database(input: DeriveInput) -> TokenStream2 {
+    // Flattened members are resolved one at a time through the macro of their group, which calls
+    // back into this expansion once all members are known
+    if let Some(invocation) = generate_flatten_invocation(&input) {
+        return invocation;
+    }
+
     let crate_path = get_crate_path();
     let name = input.ident.clone();
-    let (database_name, subsets) = extract_database_attributes(&input);
+    let (database_name, module, subsets) = extract_database_attributes(&input);
+
+    // Content structs marked with `#[group]` are groups, which are only flattened into other
+    // content structs
+    if extract_group_attribute(&input) {
+        if database_name.is_some() || module.is_some() || !subsets.is_empty() {
+            panic!("groups can't have a name, module or subsets, as they're only flattened");
+        }
+        return generate_group_macro(&input);
+    }
+    if database_name.is_none() && module.is_none() {
+        panic!("name or module for the resulting database structure needs to be supplied");
+    }
     let fields: Vec<Field> = extract_fields(&input);
 
-    let (enum_name_ident, enum_size_ident) = generate_enum_names(&database_name);
+    // Items generated in a module are named by the module, while other items are named after the
+    // database or the content struct
+    let names = match (&database_name, &module) {
+        (Some(_), Some(_)) => panic!("name and module can't both be supplied"),
+        (Some(database_name), None) => {
+            let (member, count) = generate_enum_names(database_name);
+            ItemNames {
+                member,
+                count,
+                key: generate_key_name(database_name),
+                handler: Ident::new(&format!("{}SubscriberHandler", name), Span::call_site()),
+                history: Ident::new(&format!("{}History", name), Span::call_site()),
+                database: database_name.clone(),
+                group: Ident::new(&format!("{}Group", database_name), Span::call_site()),
+                group_handler: Ident::new(&format!("{}GroupHandler", name), Span::call_site()),
+            }
+        }
+        (None, _) => ItemNames {
+            member: Ident::new("Member", Span::call_site()),
+            count: Ident::new("COUNT", Span::call_site()),
+            key: Ident::new("Key", Span::call_site()),
+            handler: Ident::new("SubscriberHandler", Span::call_site()),
+            history: Ident::new("History", Span::call_site()),
+            database: Ident::new("Database", Span::call_site()),
+            group: Ident::new("Group", Span::call_site()),
+            group_handler: Ident::new("GroupHandler", Span::call_site()),
+        },
+    };
+    let ItemNames {
+        member: enum_name_ident,
+        count: enum_size_ident,
+        key: key_name_ident,
+        handler: handler_ident,
+        history: history_ident,
+        database: database_name,
+        group: group_ident,
+        group_handler: group_handler_ident,
+    } = names;
+
+    // Items in a module share the visibility of the content struct, which is relative to the
+    // parent of the module
+    let visibility = match module {
+        Some(_) => nested_visibility(&input.vis),
+        None => input.vis.to_token_stream(),
+    };
 
-    let parameters_enum = generate_parameters_enum(&enum_name_ident, &enum_size_ident, &fields);
+    let generics = &input.generics;
+
+    let parameters_enum = generate_parameters_enum(
+        &crate_path,
+        &enum_name_ident,
+        &enum_size_ident,
+        &key_name_ident,
+        &fields,
+        generics,
+        &visibility,
+        extract_copy_attribute(&input),
+    );
 
     let content_implementation = generate_database_content_impl(
         &crate_path,
@@ -33,33 +186,136 @@ pub(crate) fn derive_database(input: TokenStream) -> TokenStream {
         &enum_name_ident,
         &enum_size_ident,
         &fields,
+        generics,
     );
 
     let subscriber_handler_impl = generate_subscriber_handler_impl(
         &crate_path,
         &name,
+        &handler_ident,
         &enum_name_ident,
-        &enum_size_ident,
+        &key_name_ident,
         &subsets,
+        &fields,
+        generics,
+    );
+
+    // Subsets in groups are notified by their group, which is named by the database. Each group
+    // is locked separately by the group handler
+    let groups = subset_groups(&subsets);
+    let group_names = (!groups.is_empty()).then_some((group_ident, group_handler_ident));
+    let group_enum = group_names
+        .as_ref()
+        .map(|(group_name, _)| generate_group_enum(group_name, &groups));
+    let group_handler_impl = group_names.as_ref().map(|(_, group_handler_ident)| {
+        generate_group_handler_impl(
+            &crate_path,
+            &name,
+            group_handler_ident,
+            &enum_name_ident,
+            &subsets,
+            generics,
+        )
+    });
+
+    let history_impl = generate_history_impl(
+        &crate_path,
+        &name,
+        &history_ident,
+        &enum_name_ident,
+        &fields,
+        generics,
     );
 
     let database_impl = generate_database_impl(
         &crate_path,
         &database_name,
         &name,
+        &handler_ident,
+        &history_ident,
         &enum_name_ident,
         &enum_size_ident,
+        &key_name_ident,
+        group_names.as_ref(),
+        &subsets,
+        &fields,
+        generics,
     );
 
-    let expanded = quote! {
+    let items = quote! {
         #parameters_enum
 
         #content_implementation
 
         #subscriber_handler_impl
 
+        #group_enum
+
+        #group_handler_impl
+
+        #history_impl
+
         #database_impl
     };
 
-    TokenStream::from(expanded)
+    // Emit the generated items in their module, where they can refer to everything the content
+    // struct can. Expands to the following
+    //
+    // pub mod my_db {
+    //     use super::*;
+    //
+    //     pub enum Member { ... }
+    //     pub struct SubscriberHandler<'a> { ... }
+    //     pub struct Database<'a>(...);
+    //     ...
+    // }
+    match module {
+        Some(module) => {
+            let visibility = &input.vis;
+            quote! {
+                #visibility mod #module {
+                    #[allow(unused_imports)]
+                    use super::*;
+
+                    #items
+                }
+            }
+        }
+        None => items,
+    }
+}
+
+// The names of the items generated for a database
+struct ItemNames {
+    member: Ident,
+    count: Ident,
+    key: Ident,
+    handler: Ident,
+    history: Ident,
+    database: Ident,
+    group: Ident,
+    group_handler: Ident,
+}
+
+// The visibility of an item generated in a child module, such that it's visible to the same
+// modules as the content struct. Items of a private content struct are `pub(super)`
+fn nested_visibility(visibility: &Visibility) -> TokenStream2 {
+    match visibility {
+        Visibility::Inherited => quote! { pub(super) },
+        Visibility::Restricted(restricted)
+            if restricted
+                .path
+                .segments
+                .first()
+                .is_some_and(|segment| segment.ident == "self" || segment.ident == "super") =>
+        {
+            let segments = restricted
+                .path
+                .segments
+                .iter()
+                .filter(|segment| segment.ident != "self");
+            quote! { pub(in super #(::#segments)*) }
+        }
+        visibility => visibility.to_token_stream(),
+    }
 }
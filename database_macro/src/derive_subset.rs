@@ -2,33 +2,92 @@ use proc_macro::TokenStream;
 use proc_macro2::Span;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{DeriveInput, Ident, parse_macro_input};
+use syn::{DeriveInput, Ident, Lit, Path, PathArguments, Type, parse_macro_input};
 
 use crate::dromedar_case::to_dromedar_case;
-use crate::enum_impl::generate_enum_names;
+use crate::enum_impl::generate_enum_paths;
 use crate::{
-    attributes::{extract_fields, extract_subset_attributes},
+    attributes::{extract_deadband_attribute, extract_fields, extract_subset_attributes},
     derive_database::Field,
     get_crate_path,
 };
 
+/// The database a subset is built from, either by the path of the database or of the module its
+/// items are generated in
+pub(crate) enum Superset {
+    Database(Path),
+    Module(Path),
+}
+
+impl Superset {
+    /// The paths of the member enum and member count of the superset
+    fn member_paths(&self) -> (Path, Path) {
+        match self {
+            Superset::Database(path) => generate_enum_paths(path),
+            Superset::Module(path) => {
+                let mut path = path.clone();
+                if let Some(segment) = path.segments.last_mut() {
+                    segment.arguments = PathArguments::None;
+                }
+                (
+                    syn::parse_quote! { #path::Member },
+                    syn::parse_quote! { #path::COUNT },
+                )
+            }
+        }
+    }
+
+    /// The generic arguments of the content of a generic superset, supplied on its last segment
+    /// such as `#[superset(MyDatabase<T, N>)]`. Returned both as type arguments and as a turbofish
+    fn generic_arguments(&self) -> (TokenStream2, TokenStream2) {
+        let path = match self {
+            Superset::Database(path) | Superset::Module(path) => path,
+        };
+
+        match path.segments.last().map(|segment| &segment.arguments) {
+            Some(PathArguments::AngleBracketed(arguments)) => {
+                let arguments = &arguments.args;
+                (quote! { <#arguments> }, quote! { ::<#arguments> })
+            }
+            Some(PathArguments::Parenthesized(_)) => {
+                panic!("superset needs to be supplied its generics on the form `MyDatabase<T>`")
+            }
+            _ => (quote! {}, quote! {}),
+        }
+    }
+}
+
+pub(crate) struct Deadband {
+    pub(crate) field_name: Ident,
+    pub(crate) threshold: Lit,
+}
+
 pub(crate) fn derive_subset(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let crate_path = get_crate_path();
     let name = input.ident.clone();
 
     let superset = extract_subset_attributes(&input);
+    let deadbands = extract_deadband_attribute(&input);
     let fields: Vec<Field> = extract_fields(&input);
 
-    let (enum_name_ident, enum_size_ident) = generate_enum_names(&superset);
+    // The member enum and count are resolved relative to the path of the superset. The count is
+    // braced when used as a generic argument, as it may be a full path
+    let (enum_path, enum_size_path) = superset.member_paths();
 
-    // Generate a check for if a specified field has been changed in the parameter change list
+    // A subset of a generic database supplies the generics of the database along with its
+    // superset, such as `#[superset(MyDatabase<T, 4>)]`, which may be generics of the subset
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (database_generics, turbofish) = superset.generic_arguments();
+
+    // Generate a check for if a specified field has been marked as changed. Elements of indexed
+    // members are checked by their index, and whole indexed members by each of their elements
     //
     // Expands to the following
     //
-    // let alice_index: usize = MyDatabaseParameters::Alice(u8::default()).into();
+    // let alice_index: usize = MyDatabaseParameters::Alice(<u8>::default()).into();
     // assert!(alice_index < MY_DATABASE_CONTENT_PARAMETERS_COUNT);
-    // if parameter_change[alice_zevs_index].is_some() {
+    // if changed(alice_index) {
     //     parameter_changed = true;
     // }
     let subset_indices: TokenStream2 = fields
@@ -39,26 +98,36 @@ pub(crate) fn derive_subset(input: TokenStream) -> TokenStream {
                 Span::call_site(),
             );
 
-            let variant_name_str = to_dromedar_case(&field.field_name.to_string());
-            let variant_ident = Ident::new(&variant_name_str, field.field_name.span());
-            let ty = &field.field_type;
+            let (_, probe) = subset_member(&enum_path, &turbofish, field);
 
-            quote! {
-                let #index_name: usize = #enum_name_ident::#variant_ident(#ty::default()).into();
-                assert!(#index_name < #enum_size_ident);
-                if parameter_change[#index_name].is_some() {
+            let check = quote! {
+                let #index_name: usize = #probe.into();
+                assert!(#index_name < #enum_size_path);
+                if changed(#index_name) {
                     parameter_changed = true;
                 }
+            };
+
+            match &field.indexed {
+                Some(indexed) => {
+                    let length = &indexed.length;
+                    quote! {
+                        for index in 0..#length {
+                            #check
+                        }
+                    }
+                }
+                None => check,
             }
         })
         .collect();
 
     // Generate a get request from a database that either gets the value from a change list or the
-    // internal contents.
+    // internal contents. Indexed members are built element by element
     //
     // Expands to the following
     //
-    // let alice = match database.internal_get(&MyDatabaseParameters::Alice(u8::default())) {
+    // let alice = match database.internal_get(&MyDatabaseParameters::Alice(<u8>::default())) {
     //     MyDatabaseParameters::Alice(value) => value,
     //     _ => unreachable!(),
     // };
@@ -66,16 +135,29 @@ pub(crate) fn derive_subset(input: TokenStream) -> TokenStream {
         .iter()
         .map(|field| {
             let field_name = field.field_name;
+            let (variant_ident, probe) = subset_member(&enum_path, &turbofish, field);
 
-            let variant_name_str = to_dromedar_case(&field.field_name.to_string());
-            let variant_ident = Ident::new(&variant_name_str, field.field_name.span());
-            let ty = &field.field_type;
-
-            quote! {
-                let #field_name = match database.internal_get(&#enum_name_ident::#variant_ident(#ty::default())) {
-                    #enum_name_ident::#variant_ident(value) => value,
-                    _ => unreachable!(),
-                };
+            match (&field.indexed, &field.element) {
+                (Some(_), _) => quote! {
+                    let #field_name = core::array::from_fn(|index| {
+                        match database.internal_get(&#probe) {
+                            #enum_path::#variant_ident(_, value) => value,
+                            _ => unreachable!(),
+                        }
+                    });
+                },
+                (None, Some(_)) => quote! {
+                    let #field_name = match database.internal_get(&#probe) {
+                        #enum_path::#variant_ident(_, value) => value,
+                        _ => unreachable!(),
+                    };
+                },
+                (None, None) => quote! {
+                    let #field_name = match database.internal_get(&#probe) {
+                        #enum_path::#variant_ident(value) => value,
+                        _ => unreachable!(),
+                    };
+                },
             }
         })
         .collect();
@@ -91,9 +173,75 @@ pub(crate) fn derive_subset(input: TokenStream) -> TokenStream {
         })
         .collect();
 
+    // Generate a subscription filter if any deadbands are supplied. Fields with a deadband only
+    // pass the filter if they have moved by more than the threshold, while all other fields pass
+    // if they have changed at all. Static subscribers only store the subset they were last
+    // notified with if the subset has a subscription filter
+    //
+    // Expands to the following
+    //
+    // fn should_notify(&self, last_notified: &Self) -> bool {
+    //     false
+    //         || database::exceeds_deadband(self.alice, last_notified.alice, 10)
+    //         || self.debbie != last_notified.debbie
+    // }
+    let (should_notify, last_notified) = if deadbands.is_empty() {
+        (quote! {}, quote! { () })
+    } else {
+        for deadband in deadbands.iter() {
+            if !fields
+                .iter()
+                .any(|field| *field.field_name == deadband.field_name)
+            {
+                panic!(
+                    "deadband field `{}` is not a member of the subset",
+                    deadband.field_name
+                );
+            }
+        }
+
+        let comparisons: TokenStream2 = fields
+            .iter()
+            .map(|field| {
+                let field_name = field.field_name;
+                match deadbands
+                    .iter()
+                    .find(|deadband| deadband.field_name == *field_name)
+                {
+                    Some(deadband) => {
+                        let threshold = &deadband.threshold;
+                        quote! {
+                            || #crate_path::exceeds_deadband(self.#field_name, last_notified.#field_name, #threshold)
+                        }
+                    }
+                    None => quote! {
+                        || self.#field_name != last_notified.#field_name
+                    },
+                }
+            })
+            .collect();
+
+        (
+            quote! {
+                fn should_notify(&self, last_notified: &Self) -> bool {
+                    false #comparisons
+                }
+            },
+            quote! { core::cell::RefCell<Option<Self>> },
+        )
+    };
+
     let expanded = quote! {
-        impl #crate_path::Subset<#enum_name_ident, #enum_size_ident> for #name {
-            fn is_subscribed(parameter_change: &#crate_path::ParameterChangeList<#enum_name_ident, #enum_size_ident>) -> bool {
+        impl #impl_generics #crate_path::Subset for #name #ty_generics #where_clause {
+            type Database = <#enum_path #database_generics as #crate_path::DatabaseMember>::Content;
+
+            fn is_subscribed(
+                parameter_change: &[Option<<Self::Database as #crate_path::DatabaseContent>::Member>],
+            ) -> bool {
+                Self::is_changed(|index| parameter_change[index].is_some())
+            }
+
+            fn is_changed(changed: impl Fn(usize) -> bool) -> bool {
                 let mut parameter_changed = false;
 
                 #subset_indices
@@ -101,7 +249,7 @@ pub(crate) fn derive_subset(input: TokenStream) -> TokenStream {
                 parameter_changed
             }
 
-            fn build_from_database(database: &dyn #crate_path::DatabaseRef<#enum_name_ident>) -> Self {
+            fn build_from_database(database: &dyn #crate_path::DatabaseRef<#enum_path #database_generics>) -> Self {
 
                 #field_construction
 
@@ -109,8 +257,67 @@ pub(crate) fn derive_subset(input: TokenStream) -> TokenStream {
                     #self_construction
                 }
             }
+
+            #should_notify
+        }
+
+        impl #impl_generics #crate_path::StaticSubset for #name #ty_generics #where_clause {
+            type LastNotified = #last_notified;
         }
     };
 
     TokenStream::from(expanded)
 }
+
+// The member variant of a subset field, along with a value of the variant used to look it up.
+// Elements of indexed members use their index, while whole indexed members use `index`
+fn subset_member(
+    enum_name: &Path,
+    turbofish: &TokenStream2,
+    field: &Field,
+) -> (Ident, TokenStream2) {
+    match (&field.indexed, &field.element) {
+        (Some(_), Some(_)) => panic!(
+            "subset member `{}` can't be both indexed and an element",
+            field.field_name
+        ),
+        (Some(indexed), None) => {
+            let variant_ident = variant_of(field.field_name);
+            let ty = &indexed.element_type;
+            let value = default_value(ty);
+            let probe = quote! { #enum_name #turbofish::#variant_ident(index, #value) };
+            (variant_ident, probe)
+        }
+        (None, Some(element)) => {
+            let variant_ident = variant_of(&element.member);
+            let ty = &field.field_type;
+            let index = &element.index;
+            let value = default_value(ty);
+            let probe = quote! { #enum_name #turbofish::#variant_ident(#index, #value) };
+            (variant_ident, probe)
+        }
+        (None, None) => {
+            let variant_ident = variant_of(field.field_name);
+            let ty = &field.field_type;
+            let value = default_value(ty);
+            let probe = quote! { #enum_name #turbofish::#variant_ident(#value) };
+            (variant_ident, probe)
+        }
+    }
+}
+
+// A default value of a member type used to look up the member. Arrays are built element by
+// element, as arrays of a generic length doesn't implement `Default`
+fn default_value(ty: &Type) -> TokenStream2 {
+    match ty {
+        Type::Array(array) => {
+            let element = default_value(&array.elem);
+            quote! { core::array::from_fn(|_| #element) }
+        }
+        _ => quote! { <#ty>::default() },
+    }
+}
+
+fn variant_of(name: &Ident) -> Ident {
+    Ident::new(&to_dromedar_case(&name.to_string()), name.span())
+}
@@ -1,7 +1,7 @@
 use proc_macro2::Span;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::Ident;
+use syn::{Generics, Ident, Path, PathArguments};
 
 use crate::{
     derive_database::Field,
@@ -17,11 +17,75 @@ pub(crate) fn generate_enum_names(database_name: &Ident) -> (Ident, Ident) {
     (enum_name_ident, enum_size_ident)
 }
 
+/// The name of the enum of member keys, which identifies a member without its value
+pub(crate) fn generate_key_name(database_name: &Ident) -> Ident {
+    Ident::new(&format!("{}Key", database_name), Span::call_site())
+}
+
+/// The name of the key mask sized for the keys of a database, such as `MyDatabaseKeyMask` or
+/// `KeyMask` in a module
+pub(crate) fn generate_key_mask_name(key_name: &Ident) -> Ident {
+    Ident::new(&format!("{}Mask", key_name), Span::call_site())
+}
+
+/// The paths of the member enum and member count of a database, resolved relative to the path of
+/// the database. A database `crate::db::MyDatabase` gives `crate::db::MyDatabaseMember` and
+/// `crate::db::MY_DATABASE_MEMBER_COUNT`
+pub(crate) fn generate_enum_paths(database_path: &Path) -> (Path, Path) {
+    let database_name = &database_path
+        .segments
+        .last()
+        .expect("the path to a database can't be empty")
+        .ident;
+    let (enum_name_ident, enum_size_ident) = generate_enum_names(database_name);
+
+    let with_last_segment = |ident: Ident| {
+        let mut path = database_path.clone();
+        if let Some(segment) = path.segments.last_mut() {
+            segment.ident = ident;
+            segment.arguments = PathArguments::None;
+        }
+        path
+    };
+
+    (
+        with_last_segment(enum_name_ident),
+        with_last_segment(enum_size_ident),
+    )
+}
+
+/// The index of each member in the parameter change list. Indexed members take up one index per
+/// element, which makes the offsets expressions of the array lengths. Also returns the total count
+pub(crate) fn generate_member_offsets(fields: &[Field]) -> (Vec<TokenStream2>, TokenStream2) {
+    let mut offsets = Vec::new();
+    let mut plain_count: usize = 0;
+    let mut lengths: Vec<&syn::Expr> = Vec::new();
+
+    for field in fields {
+        offsets.push(quote! { #plain_count #(+ #lengths)* });
+        match &field.indexed {
+            Some(indexed) => lengths.push(&indexed.length),
+            None => plain_count += 1,
+        }
+    }
+
+    (offsets, quote! { #plain_count #(+ #lengths)* })
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn generate_parameters_enum(
+    crate_path: &TokenStream2,
     enum_name: &Ident,
     enum_size: &Ident,
+    key_name: &Ident,
     fields: &[Field],
+    generics: &Generics,
+    visibility: &TokenStream2,
+    copy: bool,
 ) -> TokenStream2 {
+    // The enum takes the generics of the content struct, as its members may use them
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
     // Generate enum variants of struct members
     let mut variant_idents = Vec::new();
     let variants_tokens: TokenStream2 = fields
@@ -31,38 +95,131 @@ pub(crate) fn generate_parameters_enum(
             let variant_ident = Ident::new(&variant_name_str, field.field_name.span());
             variant_idents.push(variant_ident.clone());
 
-            let ty = &field.field_type;
-            quote! { #variant_ident(#ty), }
+            match &field.indexed {
+                Some(indexed) => {
+                    let ty = &indexed.element_type;
+                    quote! { #variant_ident(usize, #ty), }
+                }
+                None => {
+                    let ty = &field.field_type;
+                    quote! { #variant_ident(#ty), }
+                }
+            }
         })
         .collect();
 
-    // Generate From<Enum> for usize implementation
-    let from_arms: TokenStream2 = variant_idents
+    // Generate From<&Enum> and From<Enum> for usize implementations. Each element of an indexed
+    // member gets an index of its own
+    let (offsets, param_count) = generate_member_offsets(fields);
+    let from_arms: TokenStream2 = fields
         .iter()
-        .enumerate()
-        .map(|(idx, variant)| {
-            quote! { #enum_name::#variant(_) => #idx, }
+        .zip(variant_idents.iter())
+        .zip(offsets.iter())
+        .map(|((field, variant), offset)| match &field.indexed {
+            Some(indexed) => {
+                let length = &indexed.length;
+                quote! {
+                    #enum_name::#variant(index, _) => {
+                        assert!(index < #length);
+                        #offset + index
+                    }
+                }
+            }
+            None => quote! { #enum_name::#variant(_) => #offset, },
         })
         .collect();
 
-    let param_count = fields.len();
+    // Generate the hierarchical names of the members, where flattened members are separated by a
+    // dot such as `motor.speed`
+    let name_arms: TokenStream2 = fields
+        .iter()
+        .zip(variant_idents.iter())
+        .map(|(field, variant)| {
+            let name = field
+                .path
+                .iter()
+                .map(|segment| segment.to_string())
+                .collect::<Vec<_>>()
+                .join(".");
+            quote! { #enum_name::#variant(..) => #name, }
+        })
+        .collect();
+
+    // Generate the key of each member, which identifies the member without its value
+    let key_arms: TokenStream2 = variant_idents
+        .iter()
+        .map(|variant| quote! { #enum_name::#variant(..) => #key_name::#variant, })
+        .collect();
+
+    // The members are `Copy` unless opted out of with `#[no_copy]`, as members may be too large to
+    // copy or not `Copy` at all, such as bounded strings
+    let derives = if copy {
+        quote! { #[derive(Debug, Clone, Copy, PartialEq)] }
+    } else {
+        quote! { #[derive(Debug, Clone, PartialEq)] }
+    };
+
+    // The key mask takes one bit per key, rounded up to whole words
+    let key_mask_name = generate_key_mask_name(key_name);
+    let key_mask_words = fields.len().div_ceil(u64::BITS as usize);
 
     // Combine enum + From impl
     quote! {
-        pub const #enum_size: usize = #param_count;
+        #visibility const #enum_size: usize = #param_count;
+
+        /// The key of a member of the database, which identifies the member without its value
+        #[allow(dead_code)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #visibility enum #key_name {
+            #(#variant_idents,)*
+        }
+
+        /// A set of keys of the database decided at runtime, sized for all keys of the database
+        #[allow(dead_code)]
+        #visibility type #key_mask_name = #crate_path::KeyMask<#key_name, #key_mask_words>;
 
         #[allow(dead_code)]
-        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-        pub enum #enum_name {
+        #derives
+        #visibility enum #enum_name #generics #where_clause {
             #variants_tokens
         }
 
-        impl From<#enum_name> for usize {
-            fn from(value: #enum_name) -> Self {
-                match value {
+        #[automatically_derived]
+        impl #impl_generics #enum_name #ty_generics #where_clause {
+            /// The hierarchical name of the member, where members of flattened structs are
+            /// separated by a dot such as `motor.speed`
+            pub const fn name(&self) -> &'static str {
+                match self {
+                    #name_arms
+                }
+            }
+
+            /// The key of the member, which identifies the member without its value
+            pub const fn key(&self) -> #key_name {
+                match self {
+                    #key_arms
+                }
+            }
+        }
+
+        impl #impl_generics From<&#enum_name #ty_generics> for usize #where_clause {
+            fn from(value: &#enum_name #ty_generics) -> Self {
+                match *value {
                     #from_arms
                 }
             }
         }
+
+        impl #impl_generics From<#enum_name #ty_generics> for usize #where_clause {
+            fn from(value: #enum_name #ty_generics) -> Self {
+                usize::from(&value)
+            }
+        }
+
+        impl From<#key_name> for usize {
+            fn from(key: #key_name) -> Self {
+                key as usize
+            }
+        }
     }
 }
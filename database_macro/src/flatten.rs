@@ -0,0 +1,229 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    Data, DeriveInput, Fields, FieldsNamed, Ident, Type,
+    parse::{Parse, ParseStream, Parser},
+    parse_macro_input,
+    punctuated::Punctuated,
+};
+
+use crate::{
+    attributes::extract_fields,
+    derive_database::{Compare, expand_database},
+    dromedar_case::to_snake_case,
+    get_crate_path,
+};
+
+// The arguments of a flatten callback, on the form `struct member { leaves }`. The leaves are the
+// members of the group that `member` is flattened from
+struct FlattenCallback {
+    input: DeriveInput,
+    member: Ident,
+    leaves: FieldsNamed,
+}
+
+impl Parse for FlattenCallback {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            input: input.parse()?,
+            member: input.parse()?,
+            leaves: input.parse()?,
+        })
+    }
+}
+
+/// Called by the macro of a group with the members of the group. Replaces the flattened member
+/// with the members of the group and continues the expansion
+pub(crate) fn database_flatten(input: TokenStream) -> TokenStream {
+    let FlattenCallback {
+        mut input,
+        member,
+        leaves,
+    } = parse_macro_input!(input as FlattenCallback);
+
+    splice_group(&mut input, &member, leaves);
+
+    TokenStream::from(expand_database(input))
+}
+
+// Generate an invocation of the macro of the first group flattened into the struct, if any.
+// Expands to the following
+//
+// MotorConfig! { struct MyDatabaseContent { ... } motor }
+pub(crate) fn generate_flatten_invocation(input: &DeriveInput) -> Option<TokenStream2> {
+    let fields = match input.data {
+        Data::Struct(ref data_struct) => &data_struct.fields,
+        _ => panic!("Can only be applied to structs"),
+    };
+
+    let field = fields
+        .iter()
+        .find(|field| field.attrs.iter().any(|attr| attr.path.is_ident("flatten")))?;
+
+    let member = field.ident.as_ref().unwrap();
+    let mut group = match &field.ty {
+        Type::Path(type_path) => type_path.path.clone(),
+        _ => panic!("flattened member `{}` needs to be a struct", member),
+    };
+
+    // The macro of the group shares the name of the group, without any generics
+    if let Some(segment) = group.segments.last_mut() {
+        segment.arguments = syn::PathArguments::None;
+    }
+
+    Some(quote! {
+        #group! { #input #member }
+    })
+}
+
+// Generates a macro with the same name as the group, which passes the members of the group on to
+// the flatten callback. The macro is exported such that groups can be flattened into databases of
+// other crates, which requires the names of groups to be unique within a crate. Groups of the same
+// name in different modules are rejected by the compiler, as their macros are defined multiple
+// times at the root of the crate. All flattened members of the group are resolved before this is
+// called
+//
+// Expands to the following
+//
+// #[macro_export]
+// macro_rules! __database_group_motor_config {
+//     ($($continuation:tt)*) => {
+//         database::__database_flatten! { $($continuation)* { speed: u16, current: u16, } }
+//     };
+// }
+//
+// pub use __database_group_motor_config as MotorConfig;
+pub(crate) fn generate_group_macro(input: &DeriveInput) -> TokenStream2 {
+    // The macro is expanded in the crate flattening the group, where `crate` is another crate
+    let crate_path = match get_crate_path().to_string().as_str() {
+        "crate" => quote! { $crate },
+        _ => get_crate_path(),
+    };
+
+    let name = &input.ident;
+    let macro_ident = Ident::new(
+        &format!("__database_group_{}", to_snake_case(&name.to_string())),
+        Span::call_site(),
+    );
+
+    let leaves: TokenStream2 = match input.data {
+        Data::Struct(ref data_struct) => match &data_struct.fields {
+            Fields::Named(named_fields) => named_fields
+                .named
+                .iter()
+                .map(|field| {
+                    let ident = &field.ident;
+                    let ty = &field.ty;
+                    let attrs = field.attrs.iter().filter(|attr| {
+                        [
+                            "history",
+                            "derived",
+                            "database_path",
+                            "indexed",
+                            "compare",
+                            "subscribable",
+                        ]
+                        .iter()
+                        .any(|name| attr.path.is_ident(name))
+                    });
+
+                    quote! {
+                        #(#attrs)* #ident: #ty,
+                    }
+                })
+                .collect(),
+            _ => panic!("Can only be applied to structs with named fields"),
+        },
+        _ => panic!("Can only be applied to structs"),
+    };
+
+    // Validate the members of the group, such as their attributes
+    let _ = extract_fields(input);
+
+    let visibility = &input.vis;
+    quote! {
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! #macro_ident {
+            ($($continuation:tt)*) => {
+                #crate_path::__database_flatten! { $($continuation)* { #leaves } }
+            };
+        }
+
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        #visibility use #macro_ident as #name;
+    }
+}
+
+// Replaces a flattened member with the members of its group. Each member is prefixed with the
+// name of the flattened member, and carries the path to its value in the content struct
+fn splice_group(input: &mut DeriveInput, member: &Ident, leaves: FieldsNamed) {
+    let named_fields = match input.data {
+        Data::Struct(ref mut data_struct) => match data_struct.fields {
+            Fields::Named(ref mut named_fields) => named_fields,
+            _ => panic!("Can only be applied to structs with named fields"),
+        },
+        _ => panic!("Can only be applied to structs"),
+    };
+
+    // Wrap the members in a struct to reuse the field extraction of the derive macros
+    let group: DeriveInput = match syn::parse2(quote! { struct Group #leaves }) {
+        Ok(group) => group,
+        Err(error) => panic!("invalid members of flattened `{}`: {}", member, error),
+    };
+    let leaf_fields = extract_fields(&group);
+    let mut fields = Punctuated::new();
+    for field in named_fields.named.iter() {
+        if field.ident.as_ref() != Some(member) {
+            fields.push(field.clone());
+            continue;
+        }
+
+        for leaf in leaf_fields.iter() {
+            let ident = Ident::new(
+                &format!("{}_{}", member, leaf.field_name),
+                leaf.field_name.span(),
+            );
+            let ty = leaf.field_type;
+            let path = &leaf.path;
+            let history = leaf
+                .history
+                .as_ref()
+                .map(|size| quote! { #[history(#size)] });
+            let indexed = leaf.indexed.as_ref().map(|_| quote! { #[indexed] });
+            let subscribable = leaf.subscribable.then(|| quote! { #[subscribable] });
+            let compare = leaf.compare.as_ref().map(|compare| match compare {
+                Compare::Epsilon(epsilon) => quote! { #[compare(epsilon = #epsilon)] },
+                Compare::NanAware => quote! { #[compare(nan_aware)] },
+                Compare::Bitwise => quote! { #[compare(bitwise)] },
+            });
+            let derived = leaf.derived.as_ref().map(|derived| {
+                let inputs = derived
+                    .inputs
+                    .iter()
+                    .map(|input| Ident::new(&format!("{}_{}", member, input), input.span()));
+                let function = &derived.function;
+                quote! { #[derived(inputs(#(#inputs),*), with = #function)] }
+            });
+
+            let tokens = quote! {
+                #history
+                #indexed
+                #compare
+                #subscribable
+                #derived
+                #[database_path(#member, #(#path),*)]
+                #ident: #ty
+            };
+
+            match syn::Field::parse_named.parse2(tokens) {
+                Ok(field) => fields.push(field),
+                Err(error) => panic!("failed to flatten `{}`: {}", member, error),
+            }
+        }
+    }
+
+    named_fields.named = fields;
+}
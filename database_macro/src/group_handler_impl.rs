@@ -0,0 +1,253 @@
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{Generics, Ident};
+
+use crate::{
+    derive_database::{Subset, with_database_lifetime},
+    dromedar_case::to_dromedar_case,
+    subscriber_handler_impl::{generate_subscribe_functions, subset_name},
+};
+
+pub(crate) fn generate_group_handler_impl(
+    crate_path: &TokenStream2,
+    struct_name: &Ident,
+    group_handler_ident: &Ident,
+    enum_name: &Ident,
+    subsets: &[Subset],
+    generics: &Generics,
+) -> TokenStream2 {
+    // The group handler borrows its subscribers, and takes the generics of the content
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let handler_generics = with_database_lifetime(generics);
+    let (impl_generics, handler_ty_generics, where_clause) = handler_generics.split_for_impl();
+
+    let groups = subset_groups(subsets);
+
+    // Each group keeps its subscribers and the version it was last notified at in a storage of its
+    // own, which is locked separately from all other groups. Expands to the following for each
+    // group
+    //
+    // struct MyDatabaseContentGroupHandlerFast<'a> {
+    //     version: u64,
+    //     my_subset3_subscribers: [Option<database::Subscription<'a, MySubset3>>; 4],
+    //     phantom_data: core::marker::PhantomData<(&'a (), fn() -> MyDatabaseContent)>,
+    // }
+    let mut storages = TokenStream2::new();
+    let mut group_variables = TokenStream2::new();
+    let mut group_new_values = TokenStream2::new();
+    let mut subscribe_functions = TokenStream2::new();
+    let mut group_notify = TokenStream2::new();
+    let mut group_poll = TokenStream2::new();
+    for (position, group) in groups.iter().enumerate() {
+        let storage_ident = Ident::new(
+            &format!(
+                "{}{}",
+                group_handler_ident,
+                to_dromedar_case(&group.to_string())
+            ),
+            Span::call_site(),
+        );
+        let group_subsets: Vec<&Subset> = subsets
+            .iter()
+            .filter(|subset| subset.group.as_ref() == Some(*group))
+            .collect();
+
+        let mut storage_variables = TokenStream2::new();
+        let mut storage_new_values = TokenStream2::new();
+        let mut notify = TokenStream2::new();
+        let mut poll = TokenStream2::new();
+        for subset in group_subsets {
+            let path = &subset.path;
+            let variable_name = Ident::new(
+                &format!("{}_subscribers", subset_name(subset)),
+                Span::call_site(),
+            );
+
+            storage_variables.extend(quote! {
+                #variable_name: [Option<#crate_path::Subscription<'a, #path>>; 4],
+            });
+            storage_new_values.extend(quote! {
+                #variable_name: [const { None }; 4],
+            });
+
+            // Static subscribers share the subset they were last notified with, and are called
+            // before the registered subscribers
+            let static_notify = if subset.subscribers.is_empty() {
+                quote! {}
+            } else {
+                let subscribers = &subset.subscribers;
+                let last_notified = Ident::new(
+                    &format!("{}_last_notified", subset_name(subset)),
+                    Span::call_site(),
+                );
+
+                storage_variables.extend(quote! {
+                    #last_notified: <#path as #crate_path::StaticSubset>::LastNotified,
+                });
+                storage_new_values.extend(quote! {
+                    #last_notified: <<#path as #crate_path::StaticSubset>::LastNotified
+                        as #crate_path::LastNotified<#path>>::EMPTY,
+                });
+
+                quote! {
+                    if #crate_path::LastNotified::should_notify(&storage.#last_notified, &subset) {
+                        #crate_path::LastNotified::replace(&storage.#last_notified, &subset);
+                        #(#crate_path::DatabaseSubscriber::<#path>::on_set(&#subscribers, &subset);)*
+                    }
+                }
+            };
+
+            subscribe_functions.extend(generate_subscribe_functions(
+                crate_path,
+                subset,
+                quote! { &self },
+                quote! {
+                    let lock = self
+                        .#group
+                        .try_lock()
+                        .ok_or(#crate_path::DatabaseError::SubscriberLock)?;
+                    let mut storage = lock.borrow_mut();
+                },
+                quote! { storage.#variable_name },
+                &quote! { #enum_name #ty_generics },
+            ));
+
+            // Subsets are notified of all members changed after the version the group was last
+            // notified at
+            notify.extend(quote! {
+                if <#path>::is_changed(|index| member_versions[index] > since) {
+                    let subset = <#path>::build_from_database(database);
+                    #static_notify
+                    for instance in storage.#variable_name.iter().flatten() {
+                        instance.notify(&subset, database.now());
+                    }
+                }
+            });
+
+            poll.extend(quote! {
+                for instance in storage.#variable_name.iter().flatten() {
+                    instance.poll(now, || <#path>::build_from_database(database));
+                }
+            });
+        }
+
+        storages.extend(quote! {
+            struct #storage_ident #handler_generics #where_clause {
+                version: u64,
+                #storage_variables
+                phantom_data: core::marker::PhantomData<(&'a (), fn() -> #struct_name #ty_generics)>,
+            }
+        });
+
+        group_variables.extend(quote! {
+            #group: #crate_path::SpinMutex<#crate_path::RefCell<#storage_ident #handler_ty_generics>>,
+        });
+
+        group_new_values.extend(quote! {
+            #group: #crate_path::SpinMutex::new(#crate_path::RefCell::new(#storage_ident {
+                version: 0,
+                #storage_new_values
+                phantom_data: core::marker::PhantomData,
+            })),
+        });
+
+        group_notify.extend(quote! {
+            #position => {
+                let lock = self
+                    .#group
+                    .try_lock()
+                    .ok_or(#crate_path::DatabaseError::SubscriberLock)?;
+                let mut storage = lock.borrow_mut();
+                let since = core::mem::replace(&mut storage.version, version);
+                #notify
+            }
+        });
+
+        group_poll.extend(quote! {
+            if let Some(lock) = self.#group.try_lock() {
+                let storage = lock.borrow();
+                #poll
+            }
+        });
+    }
+
+    // Struct declaration and implementation for a database group handler. Implements the
+    // `DatabaseGroupHandler` trait, and expands `notify_group` to lock and notify only the group
+    quote! {
+        #storages
+
+        pub struct #group_handler_ident #handler_generics #where_clause {
+            #group_variables
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #group_handler_ident #handler_ty_generics #where_clause {
+            #subscribe_functions
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #crate_path::DatabaseGroupHandler<'a, #struct_name #ty_generics>
+            for #group_handler_ident #handler_ty_generics #where_clause
+        {
+            #[allow(clippy::declare_interior_mutable_const)]
+            const EMPTY: Self = Self {
+                #group_new_values
+            };
+
+            fn notify_group(
+                &self,
+                database: &dyn #crate_path::DatabaseRef<#enum_name #ty_generics>,
+                group: usize,
+                member_versions: &[u64],
+                version: u64,
+            ) -> Result<(), #crate_path::DatabaseError> {
+                use #crate_path::Subset;
+
+                match group {
+                    #group_notify
+                    _ => unreachable!(),
+                }
+                Ok(())
+            }
+
+            fn poll(&self, database: &dyn #crate_path::DatabaseRef<#enum_name #ty_generics>, now: u64) {
+                use #crate_path::Subset;
+
+                #group_poll
+            }
+        }
+    }
+}
+
+/// The groups of the subsets, in the order they are first declared
+pub(crate) fn subset_groups(subsets: &[Subset]) -> Vec<&Ident> {
+    let mut groups: Vec<&Ident> = Vec::new();
+    for group in subsets.iter().filter_map(|subset| subset.group.as_ref()) {
+        if !groups.contains(&group) {
+            groups.push(group);
+        }
+    }
+    groups
+}
+
+/// Generate an enum of the groups of the subsets, which are notified separately. Expands to the
+/// following
+///
+/// pub enum MyDatabaseGroup {
+///     Fast,
+///     Slow,
+/// }
+pub(crate) fn generate_group_enum(group_name: &Ident, groups: &[&Ident]) -> TokenStream2 {
+    let variants = groups
+        .iter()
+        .map(|group| Ident::new(&to_dromedar_case(&group.to_string()), group.span()));
+
+    quote! {
+        /// A group of subsets, which are notified separately from all other subsets
+        #[allow(dead_code)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum #group_name {
+            #(#variants,)*
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Generics, Ident};
+
+use crate::{derive_database::Field, dromedar_case::to_dromedar_case};
+
+pub(crate) fn generate_history_impl(
+    crate_path: &TokenStream2,
+    struct_name: &Ident,
+    history_ident: &Ident,
+    enum_name: &Ident,
+    fields: &[Field],
+    generics: &Generics,
+) -> TokenStream2 {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let history_fields: Vec<&Field> = fields
+        .iter()
+        .filter(|field| field.history.is_some())
+        .collect();
+
+    // Generates a ring buffer for each member with a history. Expands to the following
+    //
+    // bob: database::RingBuffer<u16, 32>,
+    let history_variables: TokenStream2 = history_fields
+        .iter()
+        .map(|field| {
+            let field_name = field.field_name;
+            let ty = field.field_type;
+            let size = &field.history;
+
+            quote! {
+                #field_name: #crate_path::RingBuffer<#ty, #size>,
+            }
+        })
+        .collect();
+
+    let history_new_values: TokenStream2 = history_fields
+        .iter()
+        .map(|field| {
+            let field_name = field.field_name;
+            quote! {
+                #field_name: #crate_path::RingBuffer::new(),
+            }
+        })
+        .collect();
+
+    // Generates a match arm pushing a set value to its ring buffer. Expands to the following
+    //
+    // MyDatabaseMember::Bob(value) => self.bob.push(sequence, timestamp, value.clone()),
+    let record_arms: TokenStream2 = history_fields
+        .iter()
+        .map(|field| {
+            let field_name = field.field_name;
+            let variant_name_str = to_dromedar_case(&field_name.to_string());
+            let variant_ident = Ident::new(&variant_name_str, field_name.span());
+
+            quote! {
+                #enum_name::#variant_ident(value) => self.#field_name.push(sequence, timestamp, value.clone()),
+            }
+        })
+        .collect();
+
+    let record = if history_fields.is_empty() {
+        quote! {
+            fn record(&mut self, _sequence: u32, _timestamp: Option<u64>, _parameter: &#enum_name #ty_generics) {}
+        }
+    } else {
+        quote! {
+            fn record(&mut self, sequence: u32, timestamp: Option<u64>, parameter: &#enum_name #ty_generics) {
+                #[allow(unreachable_patterns)]
+                match parameter {
+                    #record_arms
+                    _ => {}
+                }
+            }
+        }
+    };
+
+    // Struct declaration for the history of all members with a `#[history(...)]` attribute. The
+    // history takes the generics of the content struct, which not all members with a history use
+    quote! {
+        pub struct #history_ident #generics #where_clause {
+            #history_variables
+            phantom_data: core::marker::PhantomData<fn() -> #struct_name #ty_generics>,
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #crate_path::DatabaseHistory<#enum_name #ty_generics>
+            for #history_ident #ty_generics #where_clause
+        {
+            const EMPTY: Self = Self {
+                #history_new_values
+                phantom_data: core::marker::PhantomData,
+            };
+
+            #record
+        }
+    }
+}
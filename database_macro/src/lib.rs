@@ -5,6 +5,9 @@ mod derive_database;
 mod derive_subset;
 mod dromedar_case;
 mod enum_impl;
+mod flatten;
+mod group_handler_impl;
+mod history_impl;
 mod subscriber_handler_impl;
 
 use core::panic;
@@ -15,12 +18,46 @@ use quote::quote;
 
 const CRATE_NAME: &str = "database";
 
-#[proc_macro_derive(Database, attributes(name, subset))]
+/// Derive a database from a content struct, named by `#[name(...)]` or generated in a module by
+/// `#[module(...)]`. The member enum of the database is `Copy`, unless the content struct is marked
+/// with `#[no_copy]` for members that aren't `Copy`.
+///
+/// Fields can be subscribed to directly with a `FieldSubscriber` through `subscribe_to_<field>` on
+/// the subscriber handler. This is opt-in, as each field subscribed to takes up slots in the
+/// subscriber handler. Only fields marked with `#[subscribable]` get these slots and the subscribe
+/// function, and other fields are subscribed to through a subset.
+///
+/// Content structs marked with `#[group]` are instead groups of members, which are flattened into
+/// databases by `#[flatten]` members. Groups are flattened through a macro exported at the root of
+/// their crate, such that the names of groups need to be unique within a crate. Two groups of the
+/// same name fail to compile, as `__database_group_<name>` is defined multiple times
+#[proc_macro_derive(
+    Database,
+    attributes(
+        name,
+        module,
+        group,
+        subset,
+        no_copy,
+        derived,
+        history,
+        flatten,
+        indexed,
+        compare,
+        subscribable
+    )
+)]
 pub fn derive_database(input: TokenStream) -> TokenStream {
     derive_database::derive_database(input)
 }
 
-#[proc_macro_derive(Subset, attributes(superset))]
+#[doc(hidden)]
+#[proc_macro]
+pub fn __database_flatten(input: TokenStream) -> TokenStream {
+    flatten::database_flatten(input)
+}
+
+#[proc_macro_derive(Subset, attributes(superset, deadband, indexed, element))]
 pub fn derive_subset(input: TokenStream) -> TokenStream {
     derive_subset::derive_subset(input)
 }
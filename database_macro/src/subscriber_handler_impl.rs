@@ -1,136 +1,397 @@
 use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
-use syn::Ident;
+use syn::{Generics, Ident};
 
-use crate::dromedar_case::to_snake_case;
+use crate::{
+    derive_database::{Field, Subset, with_database_lifetime},
+    dromedar_case::{to_dromedar_case, to_snake_case},
+    enum_impl::{generate_key_mask_name, generate_member_offsets},
+};
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn generate_subscriber_handler_impl(
     crate_path: &TokenStream2,
     struct_name: &Ident,
+    handler_ident: &Ident,
     enum_name: &Ident,
-    enum_size: &Ident,
-    subsets: &Vec<Ident>,
+    key_name: &Ident,
+    subsets: &[Subset],
+    fields: &[Field],
+    generics: &Generics,
 ) -> TokenStream2 {
-    let handler_ident = Ident::new(
-        &format!("{}SubscriberHandler", struct_name),
-        Span::call_site(),
-    );
+    // The subscriber handler borrows its subscribers, and takes the generics of the content
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let handler_generics = with_database_lifetime(generics);
+    let (impl_generics, handler_ty_generics, where_clause) = handler_generics.split_for_impl();
 
-    // Generates a variable declaration for a subscriber handler struct implementation.
-    // Expands to the following
+    // Dynamic subscribers are registered with a mask sized for the keys of the database
+    let key_mask_name = generate_key_mask_name(key_name);
+
+    // Subsets in groups are handled by the group handler, and all other subsets by this handler
+    let subsets: Vec<&Subset> = subsets
+        .iter()
+        .filter(|subset| subset.group.is_none())
+        .collect();
+
+    // Subsets with subscribers declared at compile time call them directly, before the
+    // subscribers registered at runtime
+    let static_subsets: Vec<&Subset> = subsets
+        .iter()
+        .copied()
+        .filter(|subset| !subset.subscribers.is_empty())
+        .collect();
+
+    // Generates a variable declaration for a subscriber handler struct implementation. Static
+    // subscribers share the subset they were last notified with, which is only stored if the
+    // subset has a subscription filter. Expands to the following
     //
-    //  my_subset1_subscribers: [Option<&'a dyn database::DatabaseSubscriber<MySubset1>>; 4],
+    //  my_subset1_subscribers: [Option<database::Subscription<'a, MySubset1>>; 4],
+    //  my_subset2_subscribers: [Option<database::Subscription<'a, MySubset2>>; 4],
+    //  my_subset2_last_notified: <MySubset2 as database::StaticSubset>::LastNotified,
     let subset_variables: TokenStream2 = subsets
         .iter()
         .map(|subset| {
             let name = Ident::new(
-                &format!("{}_subscribers", to_snake_case(&subset.to_string())),
+                &format!("{}_subscribers", subset_name(subset)),
                 Span::call_site(),
             );
+            let path = &subset.path;
 
             quote! {
-                #name: [Option<&'a dyn #crate_path::DatabaseSubscriber<#subset, #enum_name, #enum_size>>; 4],
+                #name: [Option<#crate_path::Subscription<'a, #path>>; 4],
             }
         })
+        .chain(static_subsets.iter().map(|subset| {
+            let name = Ident::new(
+                &format!("{}_last_notified", subset_name(subset)),
+                Span::call_site(),
+            );
+            let path = &subset.path;
+
+            quote! {
+                #name: <#path as #crate_path::StaticSubset>::LastNotified,
+            }
+        }))
         .collect();
 
     // Generates a new implementation for a subscribers list. Expands to the following
     //
-    // my_subset1_subscribers: [None; 4],
+    // my_subset1_subscribers: [const { None }; 4],
+    // my_subset2_subscribers: [const { None }; 4],
+    // my_subset2_last_notified: <<MySubset2 as database::StaticSubset>::LastNotified
+    //     as database::LastNotified<MySubset2>>::EMPTY,
     let subset_new_values: TokenStream2 = subsets
         .iter()
         .map(|subset| {
             let name = Ident::new(
-                &format!("{}_subscribers", to_snake_case(&subset.to_string())),
+                &format!("{}_subscribers", subset_name(subset)),
+                Span::call_site(),
+            );
+
+            quote! {
+                #name: [const { None }; 4],
+            }
+        })
+        .chain(static_subsets.iter().map(|subset| {
+            let name = Ident::new(
+                &format!("{}_last_notified", subset_name(subset)),
                 Span::call_site(),
             );
+            let path = &subset.path;
 
             quote! {
-                #name: [None; 4],
+                #name: <<#path as #crate_path::StaticSubset>::LastNotified
+                    as #crate_path::LastNotified<#path>>::EMPTY,
             }
+        }))
+        .collect();
+    // Generate the subscribe functions of each subset
+    let subset_subscribe_function: TokenStream2 = subsets
+        .iter()
+        .map(|subset| {
+            let variable_name = Ident::new(
+                &format!("{}_subscribers", subset_name(subset)),
+                Span::call_site(),
+            );
+
+            generate_subscribe_functions(
+                crate_path,
+                subset,
+                quote! { &mut self },
+                quote! {},
+                quote! { self.#variable_name },
+                &quote! { #enum_name #ty_generics },
+            )
         })
         .collect();
 
-    // Generate a subscribe function. Expands to the following
+    // Subsets outside of any group are notified of the parameter change list, resuming from a
+    // cursor and stopping when the budget of subscriber calls runs out. The cursor points at a
+    // subset by its position, and at a subscriber by its position in the list of static
+    // subscribers followed by the slots of the registered subscribers. Static subscribers are
+    // filtered once, before the first of them is called. The budget is only checked right before a
+    // subscriber is called, so empty slots and unchanged subsets don't stop a finished pass.
     //
-    // pub fn subscribe_with_my_subset1(
-    //     &mut self,
-    //     subscriber: &'a dyn database::DatabaseSubscriber<MySubset1>,
-    // ) -> Result<(), database::DatabaseError> {
-    //     for instance in self.my_subset1_subscribers.iter_mut() {
-    //         if instance.is_none() {
-    //             let _ = instance.insert(subscriber);
-    //             return Ok(());
+    // Expands to the following
+    //
+    // if cursor.subset == 0 {
+    //     if MySubset2::is_subscribed(parameter_change) {
+    //         let subset = MySubset2::build_from_database(database);
+    //         if cursor.subscriber == 0 {
+    //             if self.my_subset2_last_notified.should_notify(&subset) {
+    //                 self.my_subset2_last_notified.replace(&subset);
+    //             } else {
+    //                 cursor.subscriber = 1;
+    //             }
+    //         }
+    //         if cursor.subscriber == 0 {
+    //             ...
+    //             database::DatabaseSubscriber::<MySubset2>::on_set(&crate::MY_SUBSCRIBER, &subset);
+    //             cursor.subscriber += 1;
+    //         }
+    //         while cursor.subscriber < 1 + self.my_subset2_subscribers.len() {
+    //             if let Some(instance) = &self.my_subset2_subscribers[cursor.subscriber - 1] {
+    //                 if *budget == 0 {
+    //                     return false;
+    //                 }
+    //                 if instance.notify(&subset, database.now()) {
+    //                     *budget -= 1;
+    //                 }
+    //             }
+    //             cursor.subscriber += 1;
     //         }
     //     }
-    //     Err(DatabaseError::SubscriberOverflow)
+    //     cursor.subset += 1;
+    //     cursor.subscriber = 0;
     // }
-    let subset_subscribe_function: TokenStream2 = subsets
+    let subset_notify: TokenStream2 = subsets
         .iter()
-        .map(|subset| {
+        .enumerate()
+        .map(|(position, subset)| {
+            let path = &subset.path;
+            let subscribers = &subset.subscribers;
+            let static_count = subscribers.len();
             let variable_name = Ident::new(
-                &format!("{}_subscribers", to_snake_case(&subset.to_string())),
+                &format!("{}_subscribers", subset_name(subset)),
                 Span::call_site(),
             );
 
-            let function_name = Ident::new(
-                &format!("subscribe_with_{}", to_snake_case(&subset.to_string())),
-                Span::call_site(),
-            );
+            let (static_notify, slot, end) = if subscribers.is_empty() {
+                (
+                    quote! {},
+                    quote! { cursor.subscriber },
+                    quote! { self.#variable_name.len() },
+                )
+            } else {
+                let last_notified = Ident::new(
+                    &format!("{}_last_notified", subset_name(subset)),
+                    Span::call_site(),
+                );
+                let calls = subscribers.iter().enumerate().map(|(index, subscriber)| {
+                    quote! {
+                        if cursor.subscriber == #index {
+                            if *budget == 0 {
+                                return false;
+                            }
+                            #crate_path::DatabaseSubscriber::<#path>::on_set(&#subscriber, &subset);
+                            *budget -= 1;
+                            cursor.subscriber += 1;
+                        }
+                    }
+                });
+
+                (
+                    quote! {
+                        if cursor.subscriber == 0 {
+                            if #crate_path::LastNotified::should_notify(&self.#last_notified, &subset) {
+                                #crate_path::LastNotified::replace(&self.#last_notified, &subset);
+                            } else {
+                                cursor.subscriber = #static_count;
+                            }
+                        }
+                        #(#calls)*
+                    },
+                    quote! { cursor.subscriber - #static_count },
+                    quote! { #static_count + self.#variable_name.len() },
+                )
+            };
 
             quote! {
-                pub fn #function_name(
-                    &mut self,
-                    subscriber: &'a dyn #crate_path::DatabaseSubscriber<#subset, #enum_name, #enum_size>
-                ) -> Result<(), #crate_path::DatabaseError> {
-                    for instance in self.#variable_name.iter_mut() {
-                        if instance.is_none() {
-                            let _ = instance.insert(subscriber);
-                            return Ok(());
+                if cursor.subset == #position {
+                    if <#path>::is_subscribed(parameter_change) {
+                        let subset = <#path>::build_from_database(database);
+                        #static_notify
+                        while cursor.subscriber < #end {
+                            if let Some(instance) = &self.#variable_name[#slot] {
+                                if *budget == 0 {
+                                    return false;
+                                }
+                                if instance.notify(&subset, database.now()) {
+                                    *budget -= 1;
+                                }
+                            }
+                            cursor.subscriber += 1;
                         }
                     }
-                    Err(#crate_path::DatabaseError::SubscriberOverflow)
+                    cursor.subset += 1;
+                    cursor.subscriber = 0;
                 }
             }
         })
         .collect();
 
-    // Generates a notify implementation for a specific subset. Utilizes in-built functionality in
-    // the subset trait to check if a subset is subscribed to a specific parameter, and notifies
-    // all relevant subscribers if a parameter has changed
+    // The member subscribers are notified after all subsets outside of any group
+    let member_position = subsets.len();
+
+    // Generates subscription slots for each field with a `#[subscribable]` attribute, which are
+    // notified directly with the value of the field. Elements of indexed fields are notified with
+    // their index. Expands to the following
     //
-    // Expands to the following
+    // bob_field_subscribers: [Option<&'a dyn database::FieldSubscriber<u16>>; 4],
     //
-    // {
-    //     if MySubset1::is_subscribed(parameter_change) {
-    //         let subset = MySubset1::build_from_database(database);
-    //         for instance in self.my_subset1_subscribers.iter() {
-    //             if let Some(instance) = instance {
-    //                 instance.on_set(&subset);
-    //             }
+    // pub fn subscribe_to_bob(
+    //     &mut self,
+    //     subscriber: &'a dyn database::FieldSubscriber<u16>,
+    // ) -> Result<(), database::DatabaseError> {
+    //     ...
+    // }
+    //
+    // if cursor.subset == 3 {
+    //     let element_count = 1;
+    //     let subscriber_count = self.bob_field_subscribers.len();
+    //     while cursor.subscriber < element_count * subscriber_count {
+    //         let element = cursor.subscriber / subscriber_count;
+    //         let slot = cursor.subscriber % subscriber_count;
+    //         if let (Some(MyDatabaseMember::Bob(value)), Some(subscriber)) =
+    //             (&parameter_change[1 + element], &self.bob_field_subscribers[slot])
+    //         {
+    //             ...
+    //             subscriber.on_field_change(value);
     //         }
+    //         cursor.subscriber += 1;
     //     }
+    //     cursor.subset += 1;
+    //     cursor.subscriber = 0;
     // }
-    let subset_notify: TokenStream2 = subsets
+    let (offsets, _) = generate_member_offsets(fields);
+    let mut field_variables = TokenStream2::new();
+    let mut field_new_values = TokenStream2::new();
+    let mut field_subscribe_functions = TokenStream2::new();
+    let mut field_notify = TokenStream2::new();
+    let subscribable_fields: Vec<(&Field, &TokenStream2)> = fields
+        .iter()
+        .zip(offsets.iter())
+        .filter(|(field, _)| field.subscribable)
+        .collect();
+    for (position, (field, offset)) in subscribable_fields.iter().enumerate() {
+        let field_name = field.field_name;
+        let variable_name = Ident::new(
+            &format!("{}_field_subscribers", field_name),
+            Span::call_site(),
+        );
+        let function_name = Ident::new(&format!("subscribe_to_{}", field_name), Span::call_site());
+        let variant = Ident::new(
+            &to_dromedar_case(&field_name.to_string()),
+            field_name.span(),
+        );
+        let position = member_position + 1 + position;
+
+        let (value_type, element_count, pattern, value) = match &field.indexed {
+            Some(indexed) => {
+                let element_type = &indexed.element_type;
+                let length = &indexed.length;
+                (
+                    quote! { (usize, #element_type) },
+                    quote! { #length },
+                    quote! { #enum_name::#variant(index, value) },
+                    quote! { &(*index, value.clone()) },
+                )
+            }
+            None => {
+                let field_type = field.field_type;
+                (
+                    quote! { #field_type },
+                    quote! { 1 },
+                    quote! { #enum_name::#variant(value) },
+                    quote! { value },
+                )
+            }
+        };
+
+        field_variables.extend(quote! {
+            #variable_name: [Option<&'a dyn #crate_path::FieldSubscriber<#value_type>>; 4],
+        });
+
+        field_new_values.extend(quote! {
+            #variable_name: [None; 4],
+        });
+
+        field_subscribe_functions.extend(quote! {
+            /// Subscribe to the parameter directly, without defining a subset for it. The subscriber is
+            /// notified with the value of the parameter whenever it changes
+            pub fn #function_name(
+                &mut self,
+                subscriber: &'a dyn #crate_path::FieldSubscriber<#value_type>,
+            ) -> Result<(), #crate_path::DatabaseError> {
+                for instance in self.#variable_name.iter_mut() {
+                    if instance.is_none() {
+                        let _ = instance.insert(subscriber);
+                        return Ok(());
+                    }
+                }
+                Err(#crate_path::DatabaseError::SubscriberOverflow)
+            }
+        });
+
+        field_notify.extend(quote! {
+            if cursor.subset == #position {
+                let element_count: usize = #element_count;
+                let subscriber_count = self.#variable_name.len();
+                while cursor.subscriber < element_count * subscriber_count {
+                    let element = cursor.subscriber / subscriber_count;
+                    let slot = cursor.subscriber % subscriber_count;
+                    if let (Some(#pattern), Some(subscriber)) =
+                        (&parameter_change[#offset + element], &self.#variable_name[slot])
+                    {
+                        if *budget == 0 {
+                            return false;
+                        }
+                        subscriber.on_field_change(#value);
+                        *budget -= 1;
+                    }
+                    cursor.subscriber += 1;
+                }
+                cursor.subset += 1;
+                cursor.subscriber = 0;
+            }
+        });
+    }
+
+    // Dynamic subscribers are notified after all field subscribers
+    let dynamic_position = member_position + 1 + subscribable_fields.len();
+
+    // Generates a poll implementation for a specific subset. The subset is only built if a
+    // pending subscriber is due to be notified
+    //
+    // Expands to the following
+    //
+    // for instance in self.my_subset1_subscribers.iter().flatten() {
+    //     instance.poll(now, || MySubset1::build_from_database(database));
+    // }
+    let subset_poll: TokenStream2 = subsets
         .iter()
         .map(|subset| {
             let variable_name = Ident::new(
-                &format!("{}_subscribers", to_snake_case(&subset.to_string())),
+                &format!("{}_subscribers", subset_name(subset)),
                 Span::call_site(),
             );
 
+            let subset = &subset.path;
+
             quote! {
-                {
-                    if #subset::is_subscribed(parameter_change) {
-                        let subset = #subset::build_from_database(database);
-                        for instance in self.#variable_name.iter() {
-                            if let Some(instance) = instance {
-                                instance.on_set(&subset);
-                            }
-                        }
-                    }
+                for instance in self.#variable_name.iter().flatten() {
+                    instance.poll(now, || <#subset>::build_from_database(database));
                 }
-
             }
         })
         .collect();
@@ -139,34 +400,267 @@ pub(crate) fn generate_subscriber_handler_impl(
     // `DatabaseSubscriberHandler` trait, and expands `notify_subscribers` to go through each
     // registered subset
     quote! {
-        pub struct #handler_ident<'a> {
+        pub struct #handler_ident #handler_generics #where_clause {
             #subset_variables
+            member_subscribers: [Option<&'a dyn #crate_path::MemberSubscriber<#enum_name #ty_generics>>; 4],
+            #field_variables
+            dynamic_subscribers: [Option<(
+                #key_mask_name,
+                &'a dyn #crate_path::DynamicSubscriber<#enum_name #ty_generics>,
+            )>; 4],
+            phantom_data: core::marker::PhantomData<(&'a (), fn() -> #struct_name #ty_generics)>,
         }
 
         #[automatically_derived]
-        impl<'a> #handler_ident<'a> {
+        impl #impl_generics #handler_ident #handler_ty_generics #where_clause {
             pub const fn new() -> Self {
                 Self {
                     #subset_new_values
+                    member_subscribers: [None; 4],
+                    #field_new_values
+                    dynamic_subscribers: [None; 4],
+                    phantom_data: core::marker::PhantomData,
                 }
             }
 
             #subset_subscribe_function
+
+            /// Subscribe to every parameter changed in the database. The subscriber is notified
+            /// once per changed parameter, after the subscribers of all subsets
+            pub fn subscribe_to_members(
+                &mut self,
+                subscriber: &'a dyn #crate_path::MemberSubscriber<#enum_name #ty_generics>,
+            ) -> Result<(), #crate_path::DatabaseError> {
+                for instance in self.member_subscribers.iter_mut() {
+                    if instance.is_none() {
+                        let _ = instance.insert(subscriber);
+                        return Ok(());
+                    }
+                }
+                Err(#crate_path::DatabaseError::SubscriberOverflow)
+            }
+
+            #field_subscribe_functions
+
+            /// Subscribe to the parameters in `keys`, which are decided at runtime instead of by a
+            /// subset. The subscriber is notified with the changed parameters in the mask, after all
+            /// other subscribers
+            pub fn subscribe_dynamic(
+                &mut self,
+                keys: #key_mask_name,
+                subscriber: &'a dyn #crate_path::DynamicSubscriber<#enum_name #ty_generics>,
+            ) -> Result<(), #crate_path::DatabaseError> {
+                for instance in self.dynamic_subscribers.iter_mut() {
+                    if instance.is_none() {
+                        let _ = instance.insert((keys, subscriber));
+                        return Ok(());
+                    }
+                }
+                Err(#crate_path::DatabaseError::SubscriberOverflow)
+            }
         }
 
         #[automatically_derived]
-        impl<'a> #crate_path::DatabaseSubscriberHandler<'a, #struct_name, #enum_name, #enum_size>
-            for #handler_ident<'a>
+        impl #impl_generics #crate_path::DatabaseSubscriberHandler<'a, #struct_name #ty_generics>
+            for #handler_ident #handler_ty_generics #where_clause
         {
             fn notify_subscribers(
                 &self,
-                database: &dyn #crate_path::DatabaseRef<#enum_name>,
-                parameter_change: &#crate_path::ParameterChangeList<#enum_name, #enum_size>,
+                database: &dyn #crate_path::DatabaseRef<#enum_name #ty_generics>,
+                parameter_change: &[Option<#enum_name #ty_generics>],
             ) {
+                self.notify_subscribers_budgeted(
+                    database,
+                    parameter_change,
+                    &mut #crate_path::NotifyCursor::default(),
+                    &mut usize::MAX,
+                );
+            }
+
+            fn notify_subscribers_budgeted(
+                &self,
+                database: &dyn #crate_path::DatabaseRef<#enum_name #ty_generics>,
+                parameter_change: &[Option<#enum_name #ty_generics>],
+                cursor: &mut #crate_path::NotifyCursor,
+                budget: &mut usize,
+            ) -> bool {
                 use #crate_path::Subset;
 
                 #subset_notify
+
+                // Member subscribers are notified after the subsets, once per changed parameter
+                if cursor.subset == #member_position {
+                    let subscriber_count = self.member_subscribers.len();
+                    while cursor.subscriber < parameter_change.len() * subscriber_count {
+                        let index = cursor.subscriber / subscriber_count;
+                        let slot = cursor.subscriber % subscriber_count;
+                        if let (Some(member), Some(subscriber)) =
+                            (&parameter_change[index], &self.member_subscribers[slot])
+                        {
+                            if *budget == 0 {
+                                return false;
+                            }
+                            subscriber.on_member_change(member);
+                            *budget -= 1;
+                        }
+                        cursor.subscriber += 1;
+                    }
+                    cursor.subset += 1;
+                    cursor.subscriber = 0;
+                }
+
+                // Field subscribers are notified after that, once per changed field and subscriber
+                #field_notify
+
+                // Dynamic subscribers are notified last, once per subscriber with any of its keys
+                // changed
+                if cursor.subset == #dynamic_position {
+                    while cursor.subscriber < self.dynamic_subscribers.len() {
+                        if let Some((keys, subscriber)) = &self.dynamic_subscribers[cursor.subscriber] {
+                            let is_changed = |member: &#enum_name #ty_generics| keys.contains(member.key());
+                            if parameter_change.iter().flatten().any(is_changed) {
+                                if *budget == 0 {
+                                    return false;
+                                }
+                                subscriber.on_change(
+                                    &mut parameter_change
+                                        .iter()
+                                        .flatten()
+                                        .filter(|member| is_changed(member)),
+                                    database,
+                                );
+                                *budget -= 1;
+                            }
+                        }
+                        cursor.subscriber += 1;
+                    }
+                    cursor.subset += 1;
+                    cursor.subscriber = 0;
+                }
+
+                true
+            }
+
+            fn poll(&self, database: &dyn #crate_path::DatabaseRef<#enum_name #ty_generics>, now: u64) {
+                use #crate_path::Subset;
+
+                #subset_poll
+            }
+        }
+    }
+}
+
+/// The name of a subset, as the last segment of its path without any generics
+pub(crate) fn subset_name(subset: &Subset) -> String {
+    to_snake_case(&subset.path.segments.last().unwrap().ident.to_string())
+}
+
+/// Generate a subscribe function, along with one taking a notify policy and one notifying the
+/// subscriber of the current value of the subset right away. The latter is hidden, as the database
+/// calls it with itself through `subscribe_with_<subset>_and_sync`. The subscriptions are stored in
+/// `slots`, which is accessed through `receiver` after running `lock`. Expands to the following
+///
+/// pub fn subscribe_with_my_subset1(
+///     &mut self,
+///     subscriber: &'a dyn database::DatabaseSubscriber<MySubset1>,
+/// ) -> Result<(), database::DatabaseError> {
+///     self.subscribe_with_my_subset1_policy(subscriber, database::NotifyPolicy::Immediate)
+/// }
+///
+/// pub fn subscribe_with_my_subset1_policy(
+///     &mut self,
+///     subscriber: &'a dyn database::DatabaseSubscriber<MySubset1>,
+///     policy: database::NotifyPolicy,
+/// ) -> Result<(), database::DatabaseError> {
+///     for instance in self.my_subset1_subscribers.iter_mut() {
+///         if instance.is_none() {
+///             let _ = instance.insert(database::Subscription::new(subscriber, policy));
+///             return Ok(());
+///         }
+///     }
+///     Err(DatabaseError::SubscriberOverflow)
+/// }
+///
+/// #[doc(hidden)]
+/// pub fn subscribe_with_my_subset1_and_sync(
+///     &mut self,
+///     subscriber: &'a dyn database::DatabaseSubscriber<MySubset1>,
+///     database: &dyn database::DatabaseRef<MyDatabaseMember>,
+/// ) -> Result<(), database::DatabaseError> {
+///     ...
+///     subscription.notify(&MySubset1::build_from_database(database), database.now());
+///     ...
+/// }
+pub(crate) fn generate_subscribe_functions(
+    crate_path: &TokenStream2,
+    subset: &Subset,
+    receiver: TokenStream2,
+    lock: TokenStream2,
+    slots: TokenStream2,
+    member: &TokenStream2,
+) -> TokenStream2 {
+    let function_name = Ident::new(
+        &format!("subscribe_with_{}", subset_name(subset)),
+        Span::call_site(),
+    );
+
+    let policy_function_name = Ident::new(
+        &format!("subscribe_with_{}_policy", subset_name(subset)),
+        Span::call_site(),
+    );
+
+    let sync_function_name = Ident::new(
+        &format!("subscribe_with_{}_and_sync", subset_name(subset)),
+        Span::call_site(),
+    );
+    let subset = &subset.path;
+
+    quote! {
+        pub fn #function_name(
+            #receiver,
+            subscriber: &'a dyn #crate_path::DatabaseSubscriber<#subset>
+        ) -> Result<(), #crate_path::DatabaseError> {
+            self.#policy_function_name(subscriber, #crate_path::NotifyPolicy::Immediate)
+        }
+
+        pub fn #policy_function_name(
+            #receiver,
+            subscriber: &'a dyn #crate_path::DatabaseSubscriber<#subset>,
+            policy: #crate_path::NotifyPolicy,
+        ) -> Result<(), #crate_path::DatabaseError> {
+            #lock
+            for instance in #slots.iter_mut() {
+                if instance.is_none() {
+                    let _ = instance.insert(#crate_path::Subscription::new(subscriber, policy));
+                    return Ok(());
+                }
+            }
+            Err(#crate_path::DatabaseError::SubscriberOverflow)
+        }
+
+        // Used by the database to subscribe with the current value of the subset, which is built
+        // from `database` while the subscribers are locked
+        #[doc(hidden)]
+        pub fn #sync_function_name(
+            #receiver,
+            subscriber: &'a dyn #crate_path::DatabaseSubscriber<#subset>,
+            database: &dyn #crate_path::DatabaseRef<#member>,
+        ) -> Result<(), #crate_path::DatabaseError> {
+            #lock
+            for instance in #slots.iter_mut() {
+                if instance.is_none() {
+                    let subscription = instance.insert(#crate_path::Subscription::new(
+                        subscriber,
+                        #crate_path::NotifyPolicy::Immediate,
+                    ));
+                    subscription.notify(
+                        &<#subset as #crate_path::Subset>::build_from_database(database),
+                        database.now(),
+                    );
+                    return Ok(());
+                }
             }
+            Err(#crate_path::DatabaseError::SubscriberOverflow)
         }
     }
 }
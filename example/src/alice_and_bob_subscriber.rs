@@ -14,9 +14,7 @@ pub struct AliceAndBobSubscriber {}
 
 // This subset implements the `DatabaseSubscriber` trait for the various types generated by the
 // `Database` macro
-impl DatabaseSubscriber<AliceAndBobSubset, MyDatabaseMember, MY_DATABASE_MEMBER_COUNT>
-    for AliceAndBobSubscriber
-{
+impl DatabaseSubscriber<AliceAndBobSubset> for AliceAndBobSubscriber {
     fn on_set(&self, change: &AliceAndBobSubset) {
         std::println!(
             "Alice or Bob changed! Alice: {}, Bob: {}",
@@ -14,9 +14,7 @@ pub struct BobAndDebbieSubscriber {}
 
 // This subset implements the `DatabaseSubscriber` trait for the various types generated by the
 // `Database` macro
-impl DatabaseSubscriber<BobAndDebbieSubset, MyDatabaseMember, MY_DATABASE_MEMBER_COUNT>
-    for BobAndDebbieSubscriber
-{
+impl DatabaseSubscriber<BobAndDebbieSubset> for BobAndDebbieSubscriber {
     fn on_set(&self, change: &BobAndDebbieSubset) {
         std::println!(
             "Bob or Debbie changed! Bob: {}, Debbie: {}",
@@ -19,7 +19,7 @@ fn main() {
     let alice_and_bob_subscriber = AliceAndBobSubscriber {};
     let bob_and_debbie_subscriber = BobAndDebbieSubscriber {};
     {
-        let subscriber_handler_lock = database.get_subscriber_handler().lock();
+        let subscriber_handler_lock = database.get_subscriber_handler();
         let mut subscriber_handler = subscriber_handler_lock.borrow_mut();
 
         subscriber_handler
@@ -39,6 +39,6 @@ fn main() {
     // Apply the changes and notify all subscribers. This only notifies the
     // `AliceAndBobSubscriber`, as the `BobAndDebbieSubset` doesn't contain any members that where
     // changed
-    database.multi_set(&changes);
+    database.multi_set(&changes).unwrap();
     database.notify_subscribers().unwrap();
 }
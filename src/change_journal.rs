@@ -0,0 +1,142 @@
+use core::{
+    cell::UnsafeCell,
+    mem::MaybeUninit,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+use critical_section::CriticalSection;
+
+use crate::database_error::DatabaseError;
+
+/// A `ChangeLog` keeps a record of every parameter set in a database, along with the sequence
+/// number of the set operation. This is used by a database to be able to keep a journal of a size
+/// that isn't part of the database type itself. Entries are appended in the critical section of
+/// the database
+pub trait ChangeLog<Parameter>
+where
+    Parameter: Clone + PartialEq,
+{
+    /// Append a set parameter to the log
+    fn append(&self, cs: CriticalSection, sequence: u32, parameter: &Parameter);
+}
+
+/// A `ChangeJournal` is a statically sized lock-free queue of the latest `DEPTH` parameters set in
+/// a database, which are drained by a consumer in another context. Parameters are appended by the
+/// database in its critical section, and are never blocked by the consumer. When the journal is
+/// full, new parameters are discarded and reported as overflowed on the next drain
+pub struct ChangeJournal<Parameter, const DEPTH: usize>
+where
+    Parameter: Clone + PartialEq,
+{
+    entries: [UnsafeCell<MaybeUninit<(u32, Parameter)>>; DEPTH],
+    // The positions of the next entry to write and read. These wrap around at twice the depth,
+    // which tells a full journal apart from an empty one
+    written: AtomicUsize,
+    read: AtomicUsize,
+    // The total number of discarded entries, along with the number already reported by a drain.
+    // Each counter is only stored by one side, which keeps the journal lock-free
+    overflowed: AtomicUsize,
+    reported: AtomicUsize,
+    draining: AtomicBool,
+}
+
+// The entries are only written by the producer, which is serialized by the critical section of
+// the database, and only read by the consumer, which is serialized by the draining flag
+unsafe impl<Parameter, const DEPTH: usize> Sync for ChangeJournal<Parameter, DEPTH> where
+    Parameter: Clone + PartialEq + Send
+{
+}
+
+impl<Parameter, const DEPTH: usize> ChangeJournal<Parameter, DEPTH>
+where
+    Parameter: Clone + PartialEq,
+{
+    /// Create an empty journal
+    pub const fn new() -> Self {
+        Self {
+            entries: [const { UnsafeCell::new(MaybeUninit::uninit()) }; DEPTH],
+            written: AtomicUsize::new(0),
+            read: AtomicUsize::new(0),
+            overflowed: AtomicUsize::new(0),
+            reported: AtomicUsize::new(0),
+            draining: AtomicBool::new(false),
+        }
+    }
+
+    /// Pass all entries in the journal to `on_entry` in the order they were set, leaving the
+    /// journal empty. Returns the number of entries discarded since the last drain, as the
+    /// journal was full. Only one context can drain the journal at a time, and draining it
+    /// concurrently causes a `DatabaseError`
+    pub fn drain(&self, mut on_entry: impl FnMut(u32, Parameter)) -> Result<usize, DatabaseError> {
+        if self.draining.swap(true, Ordering::Acquire) {
+            return Err(DatabaseError::JournalLock);
+        }
+
+        let written = self.written.load(Ordering::Acquire);
+        let mut read = self.read.load(Ordering::Relaxed);
+        while read != written {
+            // The entry is initialized by the producer before the written count is published,
+            // and isn't touched by it again until the read count is published
+            let (sequence, parameter) =
+                unsafe { (*self.entries[read % DEPTH].get()).assume_init_read() };
+            read = Self::next(read);
+            self.read.store(read, Ordering::Release);
+            on_entry(sequence, parameter);
+        }
+
+        let overflowed = self.overflowed.load(Ordering::Acquire);
+        let reported = self.reported.swap(overflowed, Ordering::Relaxed);
+        self.draining.store(false, Ordering::Release);
+        Ok(overflowed.wrapping_sub(reported))
+    }
+
+    // The position following `position`
+    const fn next(position: usize) -> usize {
+        (position + 1) % (2 * DEPTH)
+    }
+}
+
+impl<Parameter, const DEPTH: usize> Default for ChangeJournal<Parameter, DEPTH>
+where
+    Parameter: Clone + PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Parameter, const DEPTH: usize> ChangeLog<Parameter> for ChangeJournal<Parameter, DEPTH>
+where
+    Parameter: Clone + PartialEq,
+{
+    fn append(&self, _cs: CriticalSection, sequence: u32, parameter: &Parameter) {
+        let written = self.written.load(Ordering::Relaxed);
+        let read = self.read.load(Ordering::Acquire);
+        if DEPTH == 0 || (written + 2 * DEPTH - read) % (2 * DEPTH) >= DEPTH {
+            let overflowed = self.overflowed.load(Ordering::Relaxed);
+            self.overflowed
+                .store(overflowed.wrapping_add(1), Ordering::Release);
+            return;
+        }
+
+        // The slot has either never been written or has already been read by the consumer
+        unsafe {
+            (*self.entries[written % DEPTH].get()).write((sequence, parameter.clone()));
+        }
+        self.written.store(Self::next(written), Ordering::Release);
+    }
+}
+
+impl<Parameter, const DEPTH: usize> Drop for ChangeJournal<Parameter, DEPTH>
+where
+    Parameter: Clone + PartialEq,
+{
+    fn drop(&mut self) {
+        // Drop the entries that were never drained
+        let written = *self.written.get_mut();
+        let mut read = *self.read.get_mut();
+        while read != written {
+            unsafe { self.entries[read % DEPTH].get_mut().assume_init_drop() };
+            read = Self::next(read);
+        }
+    }
+}
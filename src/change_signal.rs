@@ -0,0 +1,8 @@
+/// A `ChangeSignal` is told when a database has changes waiting to be notified to subscribers. It
+/// is used to wake the context notifying subscribers, such as by pending a low priority
+/// interrupt, signaling an event or waking a task, instead of polling the database
+pub trait ChangeSignal {
+    /// Signal that the database has changes waiting. This is only called when the first change
+    /// after a notification is made, and is called in the critical section of the database
+    fn signal(&self);
+}
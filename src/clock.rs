@@ -0,0 +1,43 @@
+/// An `Instant` is a monotonic point in time supplied by the application, for instance from a
+/// system timer. It is used to drive rate limited and debounced subscriptions through the `poll`
+/// function of a database. The unit of the ticks is decided by the application, and should be the
+/// same as the unit used in the `NotifyPolicy` of a subscription
+pub trait Instant: Copy {
+    /// The number of ticks since some fixed point in time, such as the system start
+    fn ticks(&self) -> u64;
+}
+
+impl Instant for u32 {
+    fn ticks(&self) -> u64 {
+        *self as u64
+    }
+}
+
+impl Instant for u64 {
+    fn ticks(&self) -> u64 {
+        *self
+    }
+}
+
+/// A `Clock` is a source of the current time supplied by the application, such as a system
+/// timer. A database with a clock stamps changes when they are made, such that debounced
+/// subscribers are measured from the change itself and rate limited subscribers can be notified
+/// without waiting for the next `poll`
+pub trait Clock {
+    /// The current number of ticks, in the same unit as the `Instant` given to `poll`
+    fn now(&self) -> u64;
+}
+
+/// A `NotifyPolicy` decides when a subscriber is notified of changes to its subset. Rate limited
+/// and debounced subscribers are marked as pending by `notify_subscribers`, and notified from
+/// `poll` when the policy allows it. With a `Clock`, a rate limited subscriber whose interval has
+/// passed is notified directly instead
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyPolicy {
+    /// Notify the subscriber directly when subscribers are notified of a change
+    Immediate,
+    /// Notify the subscriber at most once every specified number of ticks
+    RateLimit(u64),
+    /// Notify the subscriber once its subset has been stable for the specified number of ticks
+    Debounce(u64),
+}
@@ -1,15 +1,85 @@
 /// The `DatabaseContent` is a structure containing all parameters in the database. This structure
 /// is made by the user and then expanded upon using the `Database` proc-macro
-pub trait DatabaseContent<Parameter, const PARAMETER_COUNT: usize>: Clone + Copy
-where
-    Parameter: Clone + Copy + Eq,
-{
+pub trait DatabaseContent {
+    /// The enum of all parameters in the database, along with their values. This is generated by
+    /// the `Database` proc-macro as `<Name>Member`
+    type Member: Clone + PartialEq;
+
+    /// The number of entries in the parameter change list, which is one per parameter and one per
+    /// element of indexed parameters
+    const COUNT: usize;
+
     /// Set the value of a parameter in the context. This function should be automatically created
     /// by the `Database` proc-macro.
-    fn set(&mut self, parameter: Parameter);
+    fn set(&mut self, parameter: Self::Member);
 
     /// Get the value of a parameter in the context. This function should be automatically created
     /// by the `Database` proc-macro. The user will presume that the parameter returned is of the
     /// same type that the one requested. All other parameters will cause the program to panic.
-    fn get(&self, parameter: &Parameter) -> Parameter;
+    fn get(&self, parameter: &Self::Member) -> Self::Member;
+
+    /// Check if a parameter has changed from its previous value. This decides whether a set
+    /// operation is notified to subscribers. The `Database` proc-macro generates a comparison per
+    /// member, where `f32` and `f64` members are compared bitwise and all other members with `!=`,
+    /// unless a `#[compare(...)]` attribute is supplied
+    fn is_changed(previous: &Self::Member, current: &Self::Member) -> bool {
+        previous != current
+    }
+
+    /// Check if a parameter is derived from other parameters. Derived parameters can't be set
+    /// directly, and are instead recomputed by `update_derived` when their inputs change. This
+    /// function is generated by the `Database` proc-macro for `#[derived(...)]` members
+    fn is_derived(_parameter: &Self::Member) -> bool {
+        false
+    }
+
+    /// Recompute all derived parameters whose inputs are marked in the `changed` list, indexed in
+    /// the same way as the parameter change list. Each recomputed parameter whose value changed is
+    /// marked as changed and passed to `on_update` together with its previous value. This function
+    /// is generated by the `Database` proc-macro for `#[derived(...)]` members
+    fn update_derived(
+        &mut self,
+        _changed: &mut [bool],
+        _on_update: &mut dyn FnMut(Self::Member, Self::Member),
+    ) {
+    }
+}
+
+/// A `DatabaseMember` is the enum of all parameters in a database. It is implemented by the
+/// `Database` proc-macro, and is used by the `Subset` proc-macro to find the content of a database
+/// from its members
+pub trait DatabaseMember: Clone + PartialEq {
+    type Content: DatabaseContent<Member = Self>;
+}
+
+/// A `Float` is a floating-point type, which can be compared to its previous value with
+/// `#[compare(bitwise)]` or `#[compare(nan_aware)]`. It is implemented for `f32` and `f64`, which
+/// are compared bitwise by default, and is used by the `Database` proc-macro such that aliases and
+/// generics of floats can be compared too
+pub trait Float: Copy + PartialEq {
+    /// Check if the value isn't a number
+    fn is_nan(self) -> bool;
+
+    /// Check if two values have the same bit pattern
+    fn bitwise_eq(self, other: Self) -> bool;
+}
+
+impl Float for f32 {
+    fn is_nan(self) -> bool {
+        f32::is_nan(self)
+    }
+
+    fn bitwise_eq(self, other: Self) -> bool {
+        self.to_bits() == other.to_bits()
+    }
+}
+
+impl Float for f64 {
+    fn is_nan(self) -> bool {
+        f64::is_nan(self)
+    }
+
+    fn bitwise_eq(self, other: Self) -> bool {
+        self.to_bits() == other.to_bits()
+    }
 }
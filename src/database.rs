@@ -1,12 +1,28 @@
-use core::sync::atomic::{AtomicBool, Ordering};
-use critical_section::{Mutex as CriticalMutex, with as critical};
+use core::{
+    cell::Cell,
+    ops::Deref,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use critical_section::{CriticalSection, Mutex as CriticalMutex, with as critical};
 
 pub use core::cell::RefCell;
 pub use spin::Mutex as SpinMutex;
+use spin::MutexGuard as SpinMutexGuard;
 
 use crate::{
-    content::DatabaseContent, database_error::DatabaseError,
-    subscriber_handler::DatabaseSubscriberHandler,
+    Subset,
+    change_journal::ChangeLog,
+    change_signal::ChangeSignal,
+    clock::{Clock, Instant},
+    content::DatabaseContent,
+    database_error::DatabaseError,
+    history::DatabaseHistory,
+    reader::{Reader, SubsetSource},
+    subscriber_handler::{
+        DatabaseGroupHandler, DatabaseSubscriberHandler, NotifyCursor, NotifyOutcome,
+        NotifyProgress,
+    },
+    undo::{JournalEntry, UndoLog},
 };
 
 /// A list of all parameters in the parameter space. These are set to some when a parameter has
@@ -15,79 +31,356 @@ use crate::{
 pub type ParameterChangeList<Parameter, const PARAMETER_COUNT: usize> =
     [Option<Parameter>; PARAMETER_COUNT];
 
+// A notification of a parameter change list that was stopped by its budget, along with where it
+// should be resumed
+type NotifyInProgress<Parameter, const PARAMETER_COUNT: usize> = Option<(
+    ParameterChangeList<Parameter, PARAMETER_COUNT>,
+    NotifyCursor,
+)>;
+
 /// Internal implementation of a database reference. This is used as the Database type cannot be
 /// templated in the subscriber handler using the subscriber handler itself, as this isn't
 /// compile-time calculable
 pub trait DatabaseRef<Parameter>
 where
-    Parameter: Clone + Copy + Eq,
+    Parameter: Clone + PartialEq,
 {
     /// Gives the same result as `get`
     fn internal_get(&self, parameter: &Parameter) -> Parameter;
+
+    /// The current time of the clock of the database, if it has one
+    fn now(&self) -> Option<u64> {
+        None
+    }
 }
 
 /// A `Database` structure is a component that keeps track of an internal content list of
-/// parameters, a list of subscriber and whether parameters has changed
+/// parameters, a list of subscriber and whether parameters has changed. It can optionally keep a
+/// history of the values set, which defaults to no history at all
 pub struct DatabaseHandler<
     'a,
     InternalContent,
     InternalSubscriberHandler,
     Parameter,
     const PARAMETER_COUNT: usize,
+    InternalHistory = (),
+    InternalGroupHandler = (),
 > where
-    InternalContent: DatabaseContent<Parameter, PARAMETER_COUNT>,
-    InternalSubscriberHandler:
-        DatabaseSubscriberHandler<'a, InternalContent, Parameter, PARAMETER_COUNT>,
-    Parameter: Clone + Copy + Eq,
+    InternalContent: DatabaseContent<Member = Parameter>,
+    InternalSubscriberHandler: DatabaseSubscriberHandler<'a, InternalContent>,
+    Parameter: Clone + PartialEq,
+    InternalHistory: DatabaseHistory<Parameter>,
+    InternalGroupHandler: DatabaseGroupHandler<'a, InternalContent>,
 {
     content: CriticalMutex<RefCell<InternalContent>>,
     change_list: CriticalMutex<RefCell<ParameterChangeList<Parameter, PARAMETER_COUNT>>>,
     subscriber_handler: SpinMutex<RefCell<InternalSubscriberHandler>>,
+    group_handler: InternalGroupHandler,
+    history: CriticalMutex<RefCell<InternalHistory>>,
+    notify_in_progress: CriticalMutex<RefCell<NotifyInProgress<Parameter, PARAMETER_COUNT>>>,
+    shadow: CriticalMutex<RefCell<Option<ParameterChangeList<Parameter, PARAMETER_COUNT>>>>,
+    last_changed: CriticalMutex<RefCell<ParameterChangeList<Parameter, PARAMETER_COUNT>>>,
+    sequence: CriticalMutex<Cell<u32>>,
+    version: CriticalMutex<Cell<u64>>,
+    member_versions: CriticalMutex<RefCell<[u64; PARAMETER_COUNT]>>,
+    undo_journal: Option<&'a dyn UndoLog<Parameter, PARAMETER_COUNT>>,
+    change_journal: Option<&'a dyn ChangeLog<Parameter>>,
+    change_signal: Option<&'a dyn ChangeSignal>,
+    clock: Option<&'a dyn Clock>,
     has_changed: AtomicBool,
+    notify_pending: AtomicBool,
     #[allow(dead_code)]
     phantom_data: Option<&'a u8>,
 }
 
-impl<'a, InternalContent, InternalSubscriberHandler, Parameter, const PARAMETER_COUNT: usize>
-    DatabaseRef<Parameter>
-    for DatabaseHandler<'a, InternalContent, InternalSubscriberHandler, Parameter, PARAMETER_COUNT>
+impl<
+    'a,
+    InternalContent,
+    InternalSubscriberHandler,
+    Parameter,
+    const PARAMETER_COUNT: usize,
+    InternalHistory,
+    InternalGroupHandler,
+> DatabaseRef<Parameter>
+    for DatabaseHandler<
+        'a,
+        InternalContent,
+        InternalSubscriberHandler,
+        Parameter,
+        PARAMETER_COUNT,
+        InternalHistory,
+        InternalGroupHandler,
+    >
 where
-    Parameter: Copy + Clone + Eq,
+    Parameter: Clone + PartialEq,
     usize: From<Parameter>,
-    InternalContent: DatabaseContent<Parameter, PARAMETER_COUNT>,
-    InternalSubscriberHandler:
-        DatabaseSubscriberHandler<'a, InternalContent, Parameter, PARAMETER_COUNT>,
+    InternalContent: DatabaseContent<Member = Parameter>,
+    InternalSubscriberHandler: DatabaseSubscriberHandler<'a, InternalContent>,
+    InternalHistory: DatabaseHistory<Parameter>,
+    InternalGroupHandler: DatabaseGroupHandler<'a, InternalContent>,
 {
     /// Glue to get the database to be referenced by a subscriber handler
     fn internal_get(&self, parameter: &Parameter) -> Parameter {
         self.get(parameter)
     }
+
+    fn now(&self) -> Option<u64> {
+        self.clock.map(|clock| clock.now())
+    }
 }
 
-impl<'a, InternalContent, InternalSubscriberHandler, Parameter, const PARAMETER_COUNT: usize>
-    DatabaseHandler<'a, InternalContent, InternalSubscriberHandler, Parameter, PARAMETER_COUNT>
+// A view of the database as it was when the notification in progress was started. Parameters
+// changed since then are read from the shadow of their previous values, as the changes are
+// notified by the next notification
+struct Snapshot<'d, Database>(&'d Database);
+
+impl<
+    'a,
+    InternalContent,
+    InternalSubscriberHandler,
+    Parameter,
+    const PARAMETER_COUNT: usize,
+    InternalHistory,
+    InternalGroupHandler,
+> DatabaseRef<Parameter>
+    for Snapshot<
+        '_,
+        DatabaseHandler<
+            'a,
+            InternalContent,
+            InternalSubscriberHandler,
+            Parameter,
+            PARAMETER_COUNT,
+            InternalHistory,
+            InternalGroupHandler,
+        >,
+    >
 where
-    Parameter: Copy + Clone + Eq,
+    Parameter: Clone + PartialEq,
     usize: From<Parameter>,
-    InternalContent: DatabaseContent<Parameter, PARAMETER_COUNT>,
-    InternalSubscriberHandler:
-        DatabaseSubscriberHandler<'a, InternalContent, Parameter, PARAMETER_COUNT>,
+    InternalContent: DatabaseContent<Member = Parameter>,
+    InternalSubscriberHandler: DatabaseSubscriberHandler<'a, InternalContent>,
+    InternalHistory: DatabaseHistory<Parameter>,
+    InternalGroupHandler: DatabaseGroupHandler<'a, InternalContent>,
+{
+    fn internal_get(&self, parameter: &Parameter) -> Parameter {
+        let index = usize::from(parameter.clone());
+        critical(|cs| {
+            let shadow = self.0.shadow.borrow(cs).borrow();
+            match shadow.as_ref().and_then(|shadow| shadow[index].clone()) {
+                Some(previous) => previous,
+                None => self.0.content.borrow(cs).borrow().get(parameter),
+            }
+        })
+    }
+
+    fn now(&self) -> Option<u64> {
+        self.0.now()
+    }
+}
+
+/// A lock on the internal subscriber handler of a database, as returned by
+/// `get_subscriber_handler`. A notification deferred while the lock is held is made when the lock
+/// is dropped, such that it isn't lost when the lock is held by the user
+pub struct SubscriberHandlerGuard<'d, InternalSubscriberHandler> {
+    lock: Option<SpinMutexGuard<'d, RefCell<InternalSubscriberHandler>>>,
+    database: &'d dyn ReleaseSubscriberHandler<InternalSubscriberHandler>,
+}
+
+impl<InternalSubscriberHandler> Deref for SubscriberHandlerGuard<'_, InternalSubscriberHandler> {
+    type Target = RefCell<InternalSubscriberHandler>;
+
+    fn deref(&self) -> &Self::Target {
+        self.lock.as_ref().unwrap()
+    }
+}
+
+impl<InternalSubscriberHandler> Drop for SubscriberHandlerGuard<'_, InternalSubscriberHandler> {
+    fn drop(&mut self) {
+        if let Some(lock) = self.lock.take() {
+            self.database.release_subscriber_handler(lock);
+        }
+    }
+}
+
+// Glue to release the lock of a subscriber guard through the database, without the guard being
+// templated on the database itself
+trait ReleaseSubscriberHandler<InternalSubscriberHandler> {
+    fn release_subscriber_handler<'l>(
+        &'l self,
+        lock: SpinMutexGuard<'l, RefCell<InternalSubscriberHandler>>,
+    );
+}
+
+impl<
+    'a,
+    InternalContent,
+    InternalSubscriberHandler,
+    Parameter,
+    const PARAMETER_COUNT: usize,
+    InternalHistory,
+    InternalGroupHandler,
+> ReleaseSubscriberHandler<InternalSubscriberHandler>
+    for DatabaseHandler<
+        'a,
+        InternalContent,
+        InternalSubscriberHandler,
+        Parameter,
+        PARAMETER_COUNT,
+        InternalHistory,
+        InternalGroupHandler,
+    >
+where
+    Parameter: Clone + PartialEq,
+    usize: From<Parameter>,
+    InternalContent: DatabaseContent<Member = Parameter>,
+    InternalSubscriberHandler: DatabaseSubscriberHandler<'a, InternalContent>,
+    InternalHistory: DatabaseHistory<Parameter>,
+    InternalGroupHandler: DatabaseGroupHandler<'a, InternalContent>,
+{
+    fn release_subscriber_handler<'l>(
+        &'l self,
+        lock: SpinMutexGuard<'l, RefCell<InternalSubscriberHandler>>,
+    ) {
+        // Notifications deferred while the lock was held are made in full
+        let mut budget = usize::MAX;
+        self.release(lock, &mut budget);
+    }
+}
+
+impl<
+    'a,
+    InternalContent,
+    InternalSubscriberHandler,
+    Parameter,
+    const PARAMETER_COUNT: usize,
+    InternalHistory,
+    InternalGroupHandler,
+    ParameterSubset,
+> SubsetSource<ParameterSubset>
+    for DatabaseHandler<
+        'a,
+        InternalContent,
+        InternalSubscriberHandler,
+        Parameter,
+        PARAMETER_COUNT,
+        InternalHistory,
+        InternalGroupHandler,
+    >
+where
+    Parameter: Clone + PartialEq,
+    usize: From<Parameter>,
+    InternalContent: DatabaseContent<Member = Parameter>,
+    InternalSubscriberHandler: DatabaseSubscriberHandler<'a, InternalContent>,
+    InternalHistory: DatabaseHistory<Parameter>,
+    InternalGroupHandler: DatabaseGroupHandler<'a, InternalContent>,
+    ParameterSubset: Subset<Database = InternalContent>,
+{
+    /// Glue to get the database to be read by a reader
+    fn version(&self) -> u64 {
+        self.version()
+    }
+
+    fn subset_changed_since(&self, version: u64) -> Option<ParameterSubset> {
+        self.subset_changed_since(version)
+    }
+}
+
+impl<
+    'a,
+    InternalContent,
+    InternalSubscriberHandler,
+    Parameter,
+    const PARAMETER_COUNT: usize,
+    InternalHistory,
+    InternalGroupHandler,
+>
+    DatabaseHandler<
+        'a,
+        InternalContent,
+        InternalSubscriberHandler,
+        Parameter,
+        PARAMETER_COUNT,
+        InternalHistory,
+        InternalGroupHandler,
+    >
+where
+    Parameter: Clone + PartialEq,
+    usize: From<Parameter>,
+    InternalContent: DatabaseContent<Member = Parameter>,
+    InternalSubscriberHandler: DatabaseSubscriberHandler<'a, InternalContent>,
+    InternalHistory: DatabaseHistory<Parameter>,
+    InternalGroupHandler: DatabaseGroupHandler<'a, InternalContent>,
 {
     /// Create a new instance if a `Database`, templated with the content, subscriber handler,
-    /// parameter enum type and the number of members in that enum
+    /// parameter enum type, the number of members in that enum, the history kept and the handler of
+    /// the groups. Derived parameters of the content are taken as they are, as this is `const`, and
+    /// should be computed with `DatabaseContent::update_derived` beforehand
     pub const fn new(
         content: InternalContent,
         subscriber_handler: InternalSubscriberHandler,
     ) -> Self {
+        // The parameter change list is sized by the database, as it can't be sized by the content
+        const { assert!(PARAMETER_COUNT == InternalContent::COUNT) };
+
         Self {
             content: CriticalMutex::new(RefCell::new(content)),
             change_list: CriticalMutex::new(RefCell::new([const { None }; PARAMETER_COUNT])),
             subscriber_handler: SpinMutex::new(RefCell::new(subscriber_handler)),
+            group_handler: InternalGroupHandler::EMPTY,
+            history: CriticalMutex::new(RefCell::new(InternalHistory::EMPTY)),
+            notify_in_progress: CriticalMutex::new(RefCell::new(None)),
+            shadow: CriticalMutex::new(RefCell::new(None)),
+            last_changed: CriticalMutex::new(RefCell::new([const { None }; PARAMETER_COUNT])),
+            sequence: CriticalMutex::new(Cell::new(0)),
+            version: CriticalMutex::new(Cell::new(0)),
+            member_versions: CriticalMutex::new(RefCell::new([0; PARAMETER_COUNT])),
+            undo_journal: None,
+            change_journal: None,
+            change_signal: None,
+            clock: None,
             has_changed: AtomicBool::new(false),
+            notify_pending: AtomicBool::new(false),
             phantom_data: None,
         }
     }
 
+    /// Record all set operations in an undo journal, which enables them to be undone and redone.
+    /// The depth of the journal is decided by the journal itself
+    pub const fn with_undo_journal(
+        mut self,
+        undo_journal: &'a dyn UndoLog<Parameter, PARAMETER_COUNT>,
+    ) -> Self {
+        self.undo_journal = Some(undo_journal);
+        self
+    }
+
+    /// Append every parameter set, along with the sequence number of its set operation, to a
+    /// change journal. Unlike the parameter change list, this keeps parameters that are set again
+    /// before subscribers are notified
+    pub const fn with_change_journal(
+        mut self,
+        change_journal: &'a dyn ChangeLog<Parameter>,
+    ) -> Self {
+        self.change_journal = Some(change_journal);
+        self
+    }
+
+    /// Signal when the database has changes waiting to be notified to subscribers, such that the
+    /// notifying context can be woken instead of polling the database
+    pub const fn with_change_signal(mut self, change_signal: &'a dyn ChangeSignal) -> Self {
+        self.change_signal = Some(change_signal);
+        self
+    }
+
+    /// Stamp changes with the time they are made, such that debounced subscribers are measured
+    /// from the change instead of from the next poll, and rate limited subscribers whose interval
+    /// has passed are notified directly by `notify_subscribers`
+    pub const fn with_clock(mut self, clock: &'a dyn Clock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
     /// Retrieve a value from the database
     pub fn get(&self, parameter: &Parameter) -> Parameter {
         critical(|cs| {
@@ -97,43 +390,182 @@ where
     }
 
     /// Set an array of parameters in a database. This will store a changed state for the provided
-    /// parameters, which later is acted upon by calling the `notify_subscribers` function
-    pub fn multi_set(&self, parameters: &[Parameter]) {
-        let mut has_changed = false;
+    /// parameters, which later is acted upon by calling the `notify_subscribers` function. Derived
+    /// parameters depending on the provided parameters are recomputed in the same critical
+    /// section. Derived parameters can't be set directly, and will cause a `DatabaseError` without
+    /// setting any of the parameters. As the member enum includes derived parameters, this is
+    /// checked at runtime, and the error is returned to the caller instead of being ignored
+    pub fn multi_set(&self, parameters: &[Parameter]) -> Result<(), DatabaseError> {
+        // Derived parameters are only to be set through their inputs
+        if parameters.iter().any(InternalContent::is_derived) {
+            return Err(DatabaseError::DerivedParameter);
+        }
 
         critical(|cs| {
-            let mut internal = self.content.borrow(cs).borrow_mut();
-            let mut change_list = self.change_list.borrow(cs).borrow_mut();
-            for parameter in parameters {
-                // Swap out the value in the internal database content
-                let reference = parameter.clone();
-                let current_value = internal.get(&reference);
-                internal.set(reference);
-
-                // Check if the state has changed
-                if reference != current_value {
-                    has_changed = true;
-                }
+            let entry = self.apply(cs, parameters.iter().cloned());
+
+            // Only operations that actually changed something are worth undoing
+            if let (Some(entry), Some(undo_journal)) = (entry, self.undo_journal) {
+                undo_journal.record(cs, entry);
+            }
+        });
+        Ok(())
+    }
+
+    /// Apply a set operation to the internal content and change list. Returns a journal entry of
+    /// the operation if any parameter changed its value
+    fn apply(
+        &self,
+        cs: CriticalSection,
+        parameters: impl Iterator<Item = Parameter>,
+    ) -> Option<JournalEntry<Parameter, PARAMETER_COUNT>> {
+        let mut has_changed = false;
+        let mut internal = self.content.borrow(cs).borrow_mut();
+        let mut change_list = self.change_list.borrow(cs).borrow_mut();
+        let mut history = self.history.borrow(cs).borrow_mut();
+        let mut shadow = self.shadow.borrow(cs).borrow_mut();
+        let mut last_changed = self.last_changed.borrow(cs).borrow_mut();
+        let mut changed = [false; PARAMETER_COUNT];
+        let mut modified = [false; PARAMETER_COUNT];
+        let mut entry = JournalEntry {
+            previous: [const { None }; PARAMETER_COUNT],
+            current: [const { None }; PARAMETER_COUNT],
+        };
+
+        // Every set operation gets a sequence number of its own, shared by all its parameters
+        let sequence = self.sequence.borrow(cs);
+        let sequence_number = sequence.get();
+        sequence.set(sequence_number.wrapping_add(1));
+        let timestamp = self.clock.map(|clock| clock.now());
+
+        for parameter in parameters {
+            let index = usize::from(parameter.clone());
+
+            // This should hard fail, as the default proc-macro implementation won't allow this
+            assert!(index < PARAMETER_COUNT);
+
+            // A notification in progress keeps reading the value it was started with
+            let current_value = internal.get(&parameter);
+            if let Some(shadow) = shadow.as_mut()
+                && shadow[index].is_none()
+            {
+                let _ = shadow[index].insert(current_value.clone());
+            }
+
+            // The value is always stored, while the comparison of the member decides whether it's
+            // a change. Values are compared to the value the parameter last changed to, such that
+            // changes within the comparison can't drift unnoticed
+            let reference = last_changed[index]
+                .get_or_insert_with(|| current_value.clone())
+                .clone();
+            internal.set(parameter.clone());
+            if InternalContent::is_changed(&reference, &parameter) {
+                has_changed = true;
+                changed[index] = true;
+                modified[index] = true;
+                let _ = last_changed[index].insert(parameter.clone());
+                let _ = change_list[index].insert(parameter.clone());
+            }
+
+            // Keep the value from before the operation, if the parameter is set multiple times
+            if entry.previous[index].is_none() {
+                let _ = entry.previous[index].insert(current_value);
+            }
+            let _ = entry.current[index].insert(parameter.clone());
+
+            history.record(sequence_number, timestamp, &parameter);
+            if let Some(change_journal) = self.change_journal {
+                change_journal.append(cs, sequence_number, &parameter);
+            }
+        }
 
-                let cloned_parameter = parameter.clone();
-                let index: usize = cloned_parameter.into();
+        // Recompute derived parameters and mark them in the change list as well
+        internal.update_derived(&mut changed, &mut |previous, current| {
+            let index = usize::from(current.clone());
+            assert!(index < PARAMETER_COUNT);
 
-                // This should hard fail, as the default proc-macro implementation won't allow this
-                assert!(index < PARAMETER_COUNT);
-                let _ = change_list[index].insert(cloned_parameter);
+            if let Some(shadow) = shadow.as_mut()
+                && shadow[index].is_none()
+            {
+                let _ = shadow[index].insert(previous);
             }
+
+            // Derived parameters are only passed on when their value has changed
+            has_changed = true;
+            modified[index] = true;
+
+            history.record(sequence_number, timestamp, &current);
+            if let Some(change_journal) = self.change_journal {
+                change_journal.append(cs, sequence_number, &current);
+            }
+            let _ = change_list[index].insert(current);
         });
 
         if has_changed {
-            self.has_changed.store(true, Ordering::SeqCst);
+            // Every set operation that changed something gets a version of its own, which is
+            // stamped on each parameter it changed
+            let version = self.version.borrow(cs);
+            version.set(version.get() + 1);
+            let mut member_versions = self.member_versions.borrow(cs).borrow_mut();
+            for (member_version, modified) in member_versions.iter_mut().zip(modified) {
+                if modified {
+                    *member_version = version.get();
+                }
+            }
+
+            // Only the first change after a notification is signaled, as the rest are notified
+            // along with it
+            if !self.has_changed.swap(true, Ordering::SeqCst)
+                && let Some(change_signal) = self.change_signal
+            {
+                change_signal.signal();
+            }
+            Some(entry)
+        } else {
+            None
         }
     }
 
     /// Set a parameter in a database. This will store a changed state for the provided
-    /// parameter, which later is acted upon by calling the `notify_subscribers` function
-    pub fn set(&self, parameter: &Parameter) {
-        let list = [parameter.clone(); 1];
-        self.multi_set(&list);
+    /// parameter, which later is acted upon by calling the `notify_subscribers` function. Derived
+    /// parameters can't be set directly, and will cause a `DatabaseError`
+    pub fn set(&self, parameter: &Parameter) -> Result<(), DatabaseError> {
+        self.multi_set(core::slice::from_ref(parameter))
+    }
+
+    /// Undo the latest set operation recorded in the undo journal, restoring the parameters to
+    /// their previous values. The restored values are notified to subscribers as any other set
+    /// operation. Returns `false` if there is nothing to undo or no undo journal is used
+    pub fn undo(&self) -> bool {
+        critical(|cs| {
+            match self
+                .undo_journal
+                .and_then(|undo_journal| undo_journal.take_undo(cs))
+            {
+                Some(entry) => {
+                    self.apply(cs, entry.previous.into_iter().flatten());
+                    true
+                }
+                None => false,
+            }
+        })
+    }
+
+    /// Redo the latest undone set operation, setting the parameters to the values of the operation
+    /// once again. Returns `false` if there is nothing to redo or no undo journal is used
+    pub fn redo(&self) -> bool {
+        critical(|cs| {
+            match self
+                .undo_journal
+                .and_then(|undo_journal| undo_journal.take_redo(cs))
+            {
+                Some(entry) => {
+                    self.apply(cs, entry.current.into_iter().flatten());
+                    true
+                }
+                None => false,
+            }
+        })
     }
 
     /// Notify all subscribers of changes made to the database. This is separated out from the set
@@ -142,57 +574,264 @@ where
     /// subscribers. If the internal subscribers are locked for any reason, this will cause a
     /// `DatabaseError`
     pub fn notify_subscribers(&self) -> Result<(), DatabaseError> {
-        // Get the has set flag and clear it in one operation to see if something has changed
-        if self.has_changed.swap(false, Ordering::SeqCst) {
-            // Retrieve a copy of the change list. This is done in a critical section
-            let parameter_change = critical(|cs| {
-                // Clone the resulting parameter change list
-                let mut parameter_change = self.change_list.borrow(cs).borrow_mut();
-                let clone = parameter_change.clone();
-
-                // Clear the original
-                for parameter in parameter_change.iter_mut() {
-                    let _ = parameter.take();
-                }
+        self.notify_subscribers_budgeted(usize::MAX).map(|_| ())
+    }
 
-                clone
-            });
+    /// Notify subscribers of changes made to the database, stopping after `max_calls` subscribers
+    /// has been notified. The next call resumes where the previous one stopped, and each change is
+    /// notified to each subscriber once. Changes made in between are notified after the ones
+    /// being notified, which keep the values they were started with. If there are changes to
+    /// notify and the internal subscribers are locked for any reason, this will cause a
+    /// `DatabaseError`
+    pub fn notify_subscribers_budgeted(
+        &self,
+        max_calls: usize,
+    ) -> Result<NotifyProgress, DatabaseError> {
+        // Nothing to notify doesn't need the subscriber handler, and succeeds even if it's locked.
+        // A notification deferred with nothing to notify is done as well
+        if !self.has_changed.load(Ordering::SeqCst)
+            && critical(|cs| self.notify_in_progress.borrow(cs).borrow().is_none())
+        {
+            self.notify_pending.store(false, Ordering::SeqCst);
+            return Ok(NotifyProgress::Complete);
+        }
 
-            // Lock the subscriber handler. This should not be allowed to be locked already, as the
-            // changes are supposed to be made before using the database
-            match self.subscriber_handler.try_lock() {
-                Some(lock) => {
-                    lock.borrow().notify_subscribers(self, &parameter_change);
-                    Ok(())
-                }
-                None => {
-                    // Put back the last known state of the notify list
-                    critical(|cs| {
-                        let mut internal_change_list = self.change_list.borrow(cs).borrow_mut();
-                        for (internal_parameter, taken_parameter) in
-                            internal_change_list.iter_mut().zip(parameter_change.iter())
-                        {
-                            if let Some(taken_parameter) = taken_parameter {
-                                // If the internal parameter hasn't been changed, as in if anyone
-                                // else has set it, put the original value back
-                                if internal_parameter.is_none() {
-                                    let _ = internal_parameter.insert(*taken_parameter);
-                                }
-                            }
-                        }
-                    });
-                    Err(DatabaseError::SubscriberLock)
-                }
+        // Lock the subscriber handler. This should not be allowed to be locked already, as the
+        // changes are supposed to be made before using the database
+        let lock = self
+            .subscriber_handler
+            .try_lock()
+            .ok_or(DatabaseError::SubscriberLock)?;
+        let mut budget = max_calls;
+
+        // Any notification deferred until now is made as part of this one
+        self.notify_pending.store(false, Ordering::SeqCst);
+        let progress = self.notify_locked(&lock.borrow(), &mut budget);
+        match progress {
+            NotifyProgress::Complete => Ok(self.release(lock, &mut budget)),
+            // A notification deferred while the lock was held is kept for the next release
+            _ if self.notify_pending.load(Ordering::SeqCst) => Ok(NotifyProgress::Deferred),
+            progress => Ok(progress),
+        }
+    }
+
+    /// Release the lock on the subscriber handler. A notification deferred while the lock was held
+    /// is made before returning, unless another context has taken the lock and makes it instead.
+    /// Returns `Deferred` if the budget runs out before the deferred notification is made, in which
+    /// case it's made by the next context releasing the lock
+    fn release<'l>(
+        &'l self,
+        mut lock: SpinMutexGuard<'l, RefCell<InternalSubscriberHandler>>,
+        budget: &mut usize,
+    ) -> NotifyProgress {
+        loop {
+            drop(lock);
+            if !self.notify_pending.load(Ordering::SeqCst) {
+                return NotifyProgress::Complete;
+            }
+            lock = match self.subscriber_handler.try_lock() {
+                Some(lock) => lock,
+                None => return NotifyProgress::Complete,
+            };
+
+            self.notify_pending.store(false, Ordering::SeqCst);
+            if self.notify_locked(&lock.borrow(), budget) == NotifyProgress::Pending {
+                self.notify_pending.store(true, Ordering::SeqCst);
+                return NotifyProgress::Deferred;
+            }
+        }
+    }
+
+    /// Notify all subscribers of changes made to the database, or defer the notification to the
+    /// context currently notifying subscribers. Unlike `notify_subscribers`, this never fails on a
+    /// locked subscriber handler, as the context holding the lock notifies the changes before
+    /// releasing it
+    pub fn notify_subscribers_or_defer(&self) -> NotifyOutcome {
+        // The marker is left before trying the lock, such that a context releasing the lock in
+        // between always sees it
+        self.notify_pending.store(true, Ordering::SeqCst);
+        match self.notify_subscribers() {
+            Ok(()) => NotifyOutcome::Inline,
+            Err(_) => NotifyOutcome::Deferred,
+        }
+    }
+
+    /// Notify subscribers of the changes in progress and all following changes, until the budget
+    /// runs out. Requires the lock on the subscriber handler
+    fn notify_locked(
+        &self,
+        handler: &InternalSubscriberHandler,
+        budget: &mut usize,
+    ) -> NotifyProgress {
+        loop {
+            // Resume the notification in progress, or start a new one
+            let (parameter_change, mut cursor) =
+                match critical(|cs| self.notify_in_progress.borrow(cs).take()) {
+                    Some(in_progress) => in_progress,
+                    // Get the has set flag and clear it in one operation to see if something has
+                    // changed
+                    None if self.has_changed.swap(false, Ordering::SeqCst) => {
+                        // Take the resulting parameter change list, leaving it cleared. Values
+                        // changed from here on are shadowed until the notification is done
+                        let parameter_change = critical(|cs| {
+                            let mut parameter_change = self.change_list.borrow(cs).borrow_mut();
+                            self.shadow
+                                .borrow(cs)
+                                .replace(Some([const { None }; PARAMETER_COUNT]));
+                            core::mem::replace(
+                                &mut *parameter_change,
+                                [const { None }; PARAMETER_COUNT],
+                            )
+                        });
+                        (parameter_change, NotifyCursor::default())
+                    }
+                    None => return NotifyProgress::Complete,
+                };
+
+            // Subsets are built from the database as it was when the notification was started,
+            // such that changes made while it's in progress are only notified by the next one
+            if !handler.notify_subscribers_budgeted(
+                &Snapshot(self),
+                &parameter_change,
+                &mut cursor,
+                budget,
+            ) {
+                critical(|cs| {
+                    self.notify_in_progress
+                        .borrow(cs)
+                        .replace(Some((parameter_change, cursor)))
+                });
+                return NotifyProgress::Pending;
+            }
+            critical(|cs| self.shadow.borrow(cs).replace(None));
+        }
+    }
+
+    /// Notify the subscribers of a group of subsets of all parameters changed since the group was
+    /// last notified. Groups keep track of their changes separately from each other and from
+    /// `notify_subscribers`, such that they can be notified from different contexts. Each group has
+    /// a lock of its own, such that a group can be notified while another context is notifying the
+    /// subscriber handler or any other group. If the group is locked, this will cause a
+    /// `DatabaseError` and the changes are kept until the group is notified again
+    pub fn notify_group(&self, group: usize) -> Result<(), DatabaseError> {
+        let (version, member_versions) = critical(|cs| {
+            (
+                self.version.borrow(cs).get(),
+                *self.member_versions.borrow(cs).borrow(),
+            )
+        });
+        self.group_handler
+            .notify_group(self, group, &member_versions, version)
+    }
+
+    /// Notify all rate limited and debounced subscribers that are pending and whose policy allows
+    /// it at the time `now`. This should be called periodically from the same context as
+    /// `notify_subscribers`, as it requires the same lock on the internal subscribers. Subscribers
+    /// of groups are polled as well, unless their group is locked
+    pub fn poll<I: Instant>(&self, now: I) -> Result<(), DatabaseError> {
+        let now = now.ticks();
+        self.group_handler.poll(self, now);
+
+        match self.subscriber_handler.try_lock() {
+            Some(lock) => {
+                lock.borrow().poll(self, now);
+
+                // Notifications deferred while polling are made in full
+                let mut budget = usize::MAX;
+                self.release(lock, &mut budget);
+                Ok(())
             }
+            None => Err(DatabaseError::SubscriberLock),
+        }
+    }
+
+    /// The version of the database, which is increased by every set operation that changes a
+    /// parameter. Used as a cursor by readers polling for changes with `changed_since`
+    pub fn version(&self) -> u64 {
+        critical(|cs| self.version.borrow(cs).get())
+    }
+
+    /// The indices in the parameter change list of all parameters changed after `version`. The
+    /// version of the database should be read before the changes, as changes made in between are
+    /// otherwise missed by the reader
+    pub fn changed_since(&self, version: u64) -> impl Iterator<Item = usize> {
+        let member_versions = critical(|cs| *self.member_versions.borrow(cs).borrow());
+        member_versions
+            .into_iter()
+            .enumerate()
+            .filter(move |(_, member_version)| *member_version > version)
+            .map(|(index, _)| index)
+    }
+
+    /// Build a subset from the database if any of its members changed after `version`. Each
+    /// reader keeps a version of its own, such that readers are independent of each other and of
+    /// the subscribers
+    pub fn subset_changed_since<ParameterSubset>(&self, version: u64) -> Option<ParameterSubset>
+    where
+        ParameterSubset: Subset<Database = InternalContent>,
+    {
+        let member_versions = critical(|cs| *self.member_versions.borrow(cs).borrow());
+        if ParameterSubset::is_changed(|index| member_versions[index] > version) {
+            Some(ParameterSubset::build_from_database(self))
         } else {
-            Ok(())
+            None
         }
     }
 
-    /// Retrieve a handle to the internal subscriber handler. Used to subscribe to different
-    /// subsets of the parameter space. This should be done before actively using the database, as
-    /// this can cause locking errors resulting in a failure to notify subscribers
-    pub fn get_subscriber_handler(&'a self) -> &'a SpinMutex<RefCell<InternalSubscriberHandler>> {
-        &self.subscriber_handler
+    /// Create a reader polling the database for changes to a subset, as an alternative to
+    /// subscribing to it. The reader only borrows the database for as long as it's used
+    pub fn reader<ParameterSubset>(&self) -> Reader<'_, ParameterSubset>
+    where
+        ParameterSubset: Subset<Database = InternalContent>,
+    {
+        Reader::new(self)
+    }
+
+    /// Borrow the content of the database in a critical section, without copying it. Used to read
+    /// large parameters, as the critical section is held until `function` returns
+    pub fn with_content<R>(&self, function: impl FnOnce(&InternalContent) -> R) -> R {
+        critical(|cs| function(&self.content.borrow(cs).borrow()))
+    }
+
+    /// Access the history of the database in a critical section. Used to read or clear the
+    /// history of specific parameters
+    pub fn with_history<R>(&self, function: impl FnOnce(&mut InternalHistory) -> R) -> R {
+        critical(|cs| function(&mut self.history.borrow(cs).borrow_mut()))
+    }
+
+    /// Lock the internal subscriber handler, spinning until it's available. Used to subscribe to
+    /// different subsets of the parameter space. This should be done before actively using the
+    /// database, as this can cause locking errors resulting in a failure to notify subscribers. A
+    /// notification deferred while the lock is held is made when the lock is dropped
+    pub fn get_subscriber_handler(&self) -> SubscriberHandlerGuard<'_, InternalSubscriberHandler> {
+        SubscriberHandlerGuard {
+            lock: Some(self.subscriber_handler.lock()),
+            database: self,
+        }
+    }
+
+    /// Lock the internal subscriber handler and call `function` with it and the database, such as
+    /// to subscribe with the current value of a subset. If the internal subscribers are locked for
+    /// any reason, this will cause a `DatabaseError`. A notification deferred while the lock was
+    /// held is made before returning
+    pub fn with_subscriber_handler<R>(
+        &self,
+        function: impl FnOnce(&mut InternalSubscriberHandler, &dyn DatabaseRef<Parameter>) -> R,
+    ) -> Result<R, DatabaseError> {
+        let lock = self
+            .subscriber_handler
+            .try_lock()
+            .ok_or(DatabaseError::SubscriberLock)?;
+        let result = function(&mut lock.borrow_mut(), self);
+
+        let mut budget = usize::MAX;
+        self.release(lock, &mut budget);
+        Ok(result)
+    }
+
+    /// Retrieve the handler of the subsets in groups. Each group is locked separately, such that
+    /// subscribers can be added to a group while another group is being notified
+    pub fn get_group_handler(&self) -> &InternalGroupHandler {
+        &self.group_handler
     }
 }
@@ -2,4 +2,6 @@
 pub enum DatabaseError {
     SubscriberOverflow,
     SubscriberLock,
+    JournalLock,
+    DerivedParameter,
 }
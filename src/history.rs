@@ -0,0 +1,139 @@
+/// A `DatabaseHistory` records the values set in a database. It is created by the `Database`
+/// proc-macro for members with a `#[history(...)]` attribute, and is updated by the database in the
+/// same critical section as the values are set
+pub trait DatabaseHistory<Parameter>
+where
+    Parameter: Clone + PartialEq,
+{
+    /// An empty history, used when constructing a database
+    const EMPTY: Self;
+
+    /// Record that a parameter was set as part of the set operation with the sequence number
+    /// `sequence`, at the time `timestamp` if the database has a clock
+    fn record(&mut self, sequence: u32, timestamp: Option<u64>, parameter: &Parameter);
+}
+
+/// A database without any history
+impl<Parameter> DatabaseHistory<Parameter> for ()
+where
+    Parameter: Clone + PartialEq,
+{
+    const EMPTY: Self = ();
+
+    fn record(&mut self, _sequence: u32, _timestamp: Option<u64>, _parameter: &Parameter) {}
+}
+
+/// A statically sized ring buffer of values, along with the sequence numbers and optional
+/// timestamps they were set with. When the buffer is full the oldest value is overwritten
+#[derive(Debug, Clone, Copy)]
+pub struct RingBuffer<T, const SIZE: usize>
+where
+    T: Clone,
+{
+    entries: [Option<(u32, Option<u64>, T)>; SIZE],
+    head: usize,
+    len: usize,
+}
+
+impl<T, const SIZE: usize> RingBuffer<T, SIZE>
+where
+    T: Clone,
+{
+    /// Create an empty ring buffer
+    pub const fn new() -> Self {
+        Self {
+            entries: [const { None }; SIZE],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Push a value to the buffer, overwriting the oldest value if the buffer is full
+    pub fn push(&mut self, sequence: u32, timestamp: Option<u64>, value: T) {
+        if SIZE == 0 {
+            return;
+        }
+
+        let _ = self.entries[self.head].insert((sequence, timestamp, value));
+        self.head = (self.head + 1) % SIZE;
+        self.len = (self.len + 1).min(SIZE);
+    }
+
+    /// Remove all values from the buffer
+    pub fn clear(&mut self) {
+        for entry in self.entries.iter_mut() {
+            let _ = entry.take();
+        }
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// The number of values in the buffer
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the buffer doesn't contain any values
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate over the values in the buffer, from the oldest to the newest
+    pub fn iter(&self) -> RingBufferIter<'_, T, SIZE> {
+        RingBufferIter {
+            buffer: self,
+            offset: 0,
+        }
+    }
+}
+
+impl<T, const SIZE: usize> Default for RingBuffer<T, SIZE>
+where
+    T: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'b, T, const SIZE: usize> IntoIterator for &'b RingBuffer<T, SIZE>
+where
+    T: Clone,
+{
+    type Item = (u32, Option<u64>, &'b T);
+    type IntoIter = RingBufferIter<'b, T, SIZE>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the values of a `RingBuffer`, from the oldest to the newest. The values are
+/// borrowed from the buffer, such that values that aren't `Copy` can be read as well
+pub struct RingBufferIter<'b, T, const SIZE: usize>
+where
+    T: Clone,
+{
+    buffer: &'b RingBuffer<T, SIZE>,
+    offset: usize,
+}
+
+impl<'b, T, const SIZE: usize> Iterator for RingBufferIter<'b, T, SIZE>
+where
+    T: Clone,
+{
+    type Item = (u32, Option<u64>, &'b T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.buffer.len {
+            return None;
+        }
+
+        // The oldest value is `len` steps behind the head
+        let index = (self.buffer.head + SIZE - self.buffer.len + self.offset) % SIZE;
+        self.offset += 1;
+        self.buffer.entries[index]
+            .as_ref()
+            .map(|(sequence, timestamp, value)| (*sequence, *timestamp, value))
+    }
+}
@@ -0,0 +1,58 @@
+use core::marker::PhantomData;
+
+/// A `KeyMask` is a set of member keys decided at runtime, such as the parameters a plugin cares
+/// about. Keys are identified by their position in the key enum generated by the `Database`
+/// proc-macro, and are stored as one bit each in `WORDS` words. The `Database` proc-macro
+/// generates a mask sized for its keys as `<Name>KeyMask`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyMask<Key, const WORDS: usize> {
+    words: [u64; WORDS],
+    phantom_data: PhantomData<Key>,
+}
+
+impl<Key, const WORDS: usize> KeyMask<Key, WORDS>
+where
+    Key: Copy + Into<usize>,
+{
+    /// Create a mask without any keys
+    pub const fn empty() -> Self {
+        Self {
+            words: [0; WORDS],
+            phantom_data: PhantomData,
+        }
+    }
+
+    /// Create a mask of all keys in `keys`
+    pub fn from_keys(keys: &[Key]) -> Self {
+        keys.iter().fold(Self::empty(), |mask, key| mask.with(*key))
+    }
+
+    /// Add a key to the mask
+    pub fn with(mut self, key: Key) -> Self {
+        let (word, bit) = Self::bit(key);
+        self.words[word] |= bit;
+        self
+    }
+
+    /// Check if a key is part of the mask
+    pub fn contains(&self, key: Key) -> bool {
+        let (word, bit) = Self::bit(key);
+        self.words[word] & bit != 0
+    }
+
+    // The word of a key in the mask, and its bit in that word
+    fn bit(key: Key) -> (usize, u64) {
+        let index: usize = key.into();
+        let bits = u64::BITS as usize;
+        (index / bits, 1 << (index % bits))
+    }
+}
+
+impl<Key, const WORDS: usize> Default for KeyMask<Key, WORDS>
+where
+    Key: Copy + Into<usize>,
+{
+    fn default() -> Self {
+        Self::empty()
+    }
+}
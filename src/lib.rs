@@ -15,14 +15,24 @@
 
 #![no_std]
 
+mod change_journal;
+mod change_signal;
+mod clock;
 mod content;
 mod database;
 mod database_error;
+mod history;
+mod key_mask;
+mod reader;
 mod subscriber_handler;
 mod subset;
+mod undo;
 
 #[cfg(test)]
 mod tests;
 
-pub use crate::{content::*, database::*, database_error::*, subscriber_handler::*, subset::*};
+pub use crate::{
+    change_journal::*, change_signal::*, clock::*, content::*, database::*, database_error::*,
+    history::*, key_mask::*, reader::*, subscriber_handler::*, subset::*, undo::*,
+};
 pub use database_macro::*;
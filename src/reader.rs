@@ -0,0 +1,51 @@
+use crate::subset::Subset;
+
+/// A `SubsetSource` is a database that subsets can be read from by their version. This is
+/// implemented by all databases for all subsets of their content, and lets a `Reader` borrow a
+/// database without being templated by all of its types
+pub trait SubsetSource<ParameterSubset>
+where
+    ParameterSubset: Subset,
+{
+    /// The current version of the database
+    fn version(&self) -> u64;
+
+    /// Build the subset if any of its members changed after `version`
+    fn subset_changed_since(&self, version: u64) -> Option<ParameterSubset>;
+}
+
+/// A `Reader` is a cursor polling a database for changes to a subset, as an alternative to
+/// subscribing to it. Each reader keeps a version of its own, which makes readers independent of
+/// each other and of the subscribers of the database. A change is never missed by a reader, but
+/// a change made while the reader is polled may be read twice
+pub struct Reader<'r, ParameterSubset>
+where
+    ParameterSubset: Subset,
+{
+    database: &'r dyn SubsetSource<ParameterSubset>,
+    version: u64,
+}
+
+impl<'r, ParameterSubset> Reader<'r, ParameterSubset>
+where
+    ParameterSubset: Subset,
+{
+    /// Create a reader of changes made to a database after this point
+    pub fn new(database: &'r dyn SubsetSource<ParameterSubset>) -> Self {
+        Self {
+            version: database.version(),
+            database,
+        }
+    }
+
+    /// Build the subset if any of its members changed since the last poll, or since the reader was
+    /// created if it hasn't been polled yet
+    pub fn poll(&mut self) -> Option<ParameterSubset> {
+        // The version is read before the subset, such that changes made in between are read again
+        // on the next poll instead of being missed
+        let version = self.database.version();
+        let subset = self.database.subset_changed_since(self.version);
+        self.version = version;
+        subset
+    }
+}
@@ -1,31 +1,277 @@
+use core::cell::{Cell, RefCell};
+
 use crate::{
-    Subset,
-    content::DatabaseContent,
-    database::{DatabaseRef, ParameterChangeList},
+    Subset, clock::NotifyPolicy, content::DatabaseContent, database::DatabaseRef,
+    database_error::DatabaseError,
 };
 
 /// A `DatabaseSubscriber` is any entity that needs to subscribe to a subset of parameters in a
 /// database. This subset is decided by the `ParameterSubset`. A parameter subset is a struct that
 /// is registered with the `Database` as one permutation of variables present in the database
-pub trait DatabaseSubscriber<ParameterSubset, Parameter, const PARAMETER_COUNT: usize>
+pub trait DatabaseSubscriber<ParameterSubset>
 where
-    Parameter: Clone + Copy + Eq,
-    ParameterSubset: Subset<Parameter, PARAMETER_COUNT> + Clone + Copy,
+    ParameterSubset: Subset,
 {
     fn on_set(&self, change: &ParameterSubset);
 }
 
+/// A `MemberSubscriber` is any entity that needs to know of every parameter changed in a
+/// database, such as loggers and protocol bridges. It is notified once per changed parameter,
+/// without a subset
+pub trait MemberSubscriber<Member>
+where
+    Member: Clone + PartialEq,
+{
+    fn on_member_change(&self, member: &Member);
+}
+
+/// A `FieldSubscriber` is any entity that needs to subscribe to a single parameter in a
+/// database, without defining a subset for it. It is notified directly with the value of the
+/// parameter, or with the index and value of an element of an indexed parameter. Only parameters
+/// marked with `#[subscribable]` can be subscribed to this way
+pub trait FieldSubscriber<Value> {
+    fn on_field_change(&self, value: &Value);
+}
+
+/// A `DynamicSubscriber` is any entity that subscribes to a set of parameters decided at runtime
+/// instead of a subset, such as plugins. It is registered along with a `KeyMask` sized for the keys
+/// of the database, and is notified with the changed parameters in the mask along with a read view
+/// of the database
+pub trait DynamicSubscriber<Member>
+where
+    Member: Clone + PartialEq,
+{
+    fn on_change(
+        &self,
+        changed: &mut dyn Iterator<Item = &Member>,
+        database: &dyn DatabaseRef<Member>,
+    );
+}
+
+/// A `Subscription` is a subscriber registered in a subscriber handler, along with the subset it
+/// was last notified with. The last notified subset is used to filter out changes that doesn't
+/// pass the subscription filters of the subset, such as `#[deadband(...)]`. The subscription also
+/// keeps track of when the subscriber should be notified according to its `NotifyPolicy`
+pub struct Subscription<'a, ParameterSubset>
+where
+    ParameterSubset: Subset,
+{
+    subscriber: &'a dyn DatabaseSubscriber<ParameterSubset>,
+    last_notified: RefCell<Option<ParameterSubset>>,
+    policy: NotifyPolicy,
+    pending: Cell<bool>,
+    stable_since: Cell<Option<u64>>,
+    last_notified_at: Cell<Option<u64>>,
+}
+
+impl<'a, ParameterSubset> Subscription<'a, ParameterSubset>
+where
+    ParameterSubset: Subset,
+{
+    /// Create a new subscription for a subscriber that hasn't been notified yet
+    pub const fn new(
+        subscriber: &'a dyn DatabaseSubscriber<ParameterSubset>,
+        policy: NotifyPolicy,
+    ) -> Self {
+        Self {
+            subscriber,
+            last_notified: RefCell::new(None),
+            policy,
+            pending: Cell::new(false),
+            stable_since: Cell::new(None),
+            last_notified_at: Cell::new(None),
+        }
+    }
+
+    /// Notify the subscriber of a change to its subset, made at the time `now` if the database has
+    /// a clock. Subscriptions with a rate limit or debounce policy are marked as pending, and are
+    /// notified later on from `poll`. Rate limited subscriptions whose interval has passed at
+    /// `now` are notified directly. Returns `true` if the subscriber was called
+    pub fn notify(&self, subset: &ParameterSubset, now: Option<u64>) -> bool {
+        match self.policy {
+            NotifyPolicy::Immediate => self.deliver(subset),
+            NotifyPolicy::RateLimit(interval) => match now {
+                Some(now) if self.interval_passed(now, interval) => {
+                    self.pending.set(false);
+                    self.deliver_at(now, subset)
+                }
+                _ => {
+                    self.pending.set(true);
+                    false
+                }
+            },
+            NotifyPolicy::Debounce(_) => {
+                // Restart the stable period from the change, or from the next poll if the time of
+                // the change isn't known
+                self.pending.set(true);
+                self.stable_since.set(now);
+                false
+            }
+        }
+    }
+
+    /// Notify a pending subscriber if its policy allows it at the time `now`. The subset is only
+    /// built if the subscriber is notified
+    pub fn poll(&self, now: u64, build_subset: impl FnOnce() -> ParameterSubset) {
+        if !self.pending.get() {
+            return;
+        }
+
+        let should_deliver = match self.policy {
+            NotifyPolicy::Immediate => true,
+            NotifyPolicy::RateLimit(interval) => self.interval_passed(now, interval),
+            NotifyPolicy::Debounce(window) => {
+                let stable_since = self.stable_since.get().unwrap_or(now);
+                self.stable_since.set(Some(stable_since));
+                now.wrapping_sub(stable_since) >= window
+            }
+        };
+
+        if should_deliver {
+            self.pending.set(false);
+            self.stable_since.set(None);
+            self.deliver_at(now, &build_subset());
+        }
+    }
+
+    /// Check if the rate limit `interval` has passed at the time `now` since the subscriber was
+    /// last notified
+    fn interval_passed(&self, now: u64, interval: u64) -> bool {
+        match self.last_notified_at.get() {
+            Some(last_notified_at) => now.wrapping_sub(last_notified_at) >= interval,
+            None => true,
+        }
+    }
+
+    /// Pass a subset on to the subscriber at the time `now`. The time is only kept if the
+    /// subscriber was called, as a subset filtered out doesn't count towards the rate limit
+    fn deliver_at(&self, now: u64, subset: &ParameterSubset) -> bool {
+        let delivered = self.deliver(subset);
+        if delivered {
+            self.last_notified_at.set(Some(now));
+        }
+        delivered
+    }
+
+    /// Pass a subset on to the subscriber. The first change is always passed on, after which the
+    /// subset decides if the change is large enough to be notified
+    fn deliver(&self, subset: &ParameterSubset) -> bool {
+        if let Some(last_notified) = self.last_notified.borrow().as_ref()
+            && !subset.should_notify(last_notified)
+        {
+            return false;
+        }
+
+        self.last_notified.replace(Some(subset.clone()));
+        self.subscriber.on_set(subset);
+        true
+    }
+}
+
+/// The progress of a budgeted notification of subscribers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyProgress {
+    /// All changes have been notified
+    Complete,
+    /// The budget ran out before all changes were notified, and the next call resumes from here
+    Pending,
+    /// The budget ran out while a notification deferred by another context was waiting. It's made
+    /// by the next call, or by the next context releasing the subscriber handler
+    Deferred,
+}
+
+/// The outcome of a notification that is deferred if another context is notifying subscribers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyOutcome {
+    /// The subscribers were notified by the calling context
+    Inline,
+    /// The subscribers are notified by the context currently notifying them
+    Deferred,
+}
+
+/// The position of a budgeted notification in a subscriber handler, as the index of a subset and
+/// of a subscriber to that subset
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NotifyCursor {
+    pub subset: usize,
+    pub subscriber: usize,
+}
+
 /// A `DatabaseSubscriberHandler` is an handler that is built automatically using the `Database`
 /// proc-macro. This handler will go through a list of parameters and notify all subscribers
 /// relevant to the changes
-pub trait DatabaseSubscriberHandler<'a, InternalContent, Parameter, const PARAMETER_COUNT: usize>
+pub trait DatabaseSubscriberHandler<'a, InternalContent>
 where
-    Parameter: Clone + Copy + Eq,
-    InternalContent: DatabaseContent<Parameter, PARAMETER_COUNT>,
+    InternalContent: DatabaseContent,
 {
     fn notify_subscribers(
         &self,
-        database: &dyn DatabaseRef<Parameter>,
-        parameter_change: &ParameterChangeList<Parameter, PARAMETER_COUNT>,
+        database: &dyn DatabaseRef<InternalContent::Member>,
+        parameter_change: &[Option<InternalContent::Member>],
     );
+
+    /// Notify the subscribers of the subsets relevant to the changes, starting from `cursor` and
+    /// stopping when `budget` subscribers has been called. Each called subscriber decreases the
+    /// budget, and the cursor is left where the notification should be resumed. Returns `true` if
+    /// all subscribers were notified. Handlers that doesn't implement this notify all subscribers
+    /// at once
+    fn notify_subscribers_budgeted(
+        &self,
+        database: &dyn DatabaseRef<InternalContent::Member>,
+        parameter_change: &[Option<InternalContent::Member>],
+        _cursor: &mut NotifyCursor,
+        _budget: &mut usize,
+    ) -> bool {
+        self.notify_subscribers(database, parameter_change);
+        true
+    }
+
+    /// Notify all pending rate limited and debounced subscribers whose policy allows it at the
+    /// time `now`. Handlers without such subscribers doesn't need to implement this
+    fn poll(&self, _database: &dyn DatabaseRef<InternalContent::Member>, _now: u64) {}
+}
+
+/// A `DatabaseGroupHandler` is a handler of the subsets in groups, built automatically using the
+/// `Database` proc-macro. Each group has a lock and subscribers of its own, such that a group can
+/// be notified while the subscriber handler or any other group is locked
+pub trait DatabaseGroupHandler<'a, InternalContent>
+where
+    InternalContent: DatabaseContent,
+{
+    /// A handler without any subscribers, used when constructing a database
+    const EMPTY: Self;
+
+    /// Notify the subscribers of the subsets in `group` of all parameters changed after the group
+    /// was last notified. The group keeps the `version` it was notified at, and each parameter is
+    /// compared by its entry in `member_versions`. If the group is locked, this will cause a
+    /// `DatabaseError` and the changes are kept until the group is notified again
+    fn notify_group(
+        &self,
+        database: &dyn DatabaseRef<InternalContent::Member>,
+        group: usize,
+        member_versions: &[u64],
+        version: u64,
+    ) -> Result<(), DatabaseError>;
+
+    /// Notify all pending rate limited and debounced subscribers of the groups whose policy allows
+    /// it at the time `now`. Groups that are locked are polled the next time instead
+    fn poll(&self, _database: &dyn DatabaseRef<InternalContent::Member>, _now: u64) {}
+}
+
+/// A database without any groups
+impl<'a, InternalContent> DatabaseGroupHandler<'a, InternalContent> for ()
+where
+    InternalContent: DatabaseContent,
+{
+    const EMPTY: Self = ();
+
+    fn notify_group(
+        &self,
+        _database: &dyn DatabaseRef<InternalContent::Member>,
+        _group: usize,
+        _member_versions: &[u64],
+        _version: u64,
+    ) -> Result<(), DatabaseError> {
+        Ok(())
+    }
 }
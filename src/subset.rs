@@ -1,11 +1,98 @@
-use crate::{DatabaseRef, ParameterChangeList};
+use core::cell::RefCell;
+use core::ops::Sub;
 
-pub trait Subset<Parameter, const PARAMETER_COUNT: usize>: Clone + Copy
+use crate::{DatabaseContent, DatabaseRef};
+
+/// A `Subset` is a permutation of the parameters in a database, which subscribers are notified
+/// with. It is generated by the `Subset` proc-macro
+pub trait Subset: Clone {
+    /// The content of the database the subset is built from
+    type Database: DatabaseContent;
+
+    /// Check if the parameter change list contains any changes relevant to the subset
+    fn is_subscribed(
+        parameter_change: &[Option<<Self::Database as DatabaseContent>::Member>],
+    ) -> bool;
+
+    /// Check if any member of the subset is marked as changed by `changed`, which is given the
+    /// index of each member in the parameter change list. Defaults to checking every member of
+    /// the database, and is generated by the `Subset` proc-macro to only check the members of the
+    /// subset
+    fn is_changed(changed: impl Fn(usize) -> bool) -> bool {
+        (0..<Self::Database as DatabaseContent>::COUNT).any(changed)
+    }
+
+    fn build_from_database(
+        database: &dyn DatabaseRef<<Self::Database as DatabaseContent>::Member>,
+    ) -> Self;
+
+    /// Check if a subscriber that was last notified with `last_notified` should be notified with
+    /// this subset. Subsets without any subscription filters always notify. This is generated by
+    /// the `Subset` proc-macro when a `#[deadband(...)]` attribute is supplied
+    fn should_notify(&self, _last_notified: &Self) -> bool {
+        true
+    }
+}
+
+/// Check if a value has moved by more than `threshold` from a previous value. Used by the
+/// `Subset` proc-macro to implement `#[deadband(...)]` filters
+pub fn exceeds_deadband<T>(value: T, previous: T, threshold: T) -> bool
 where
-    Parameter: Clone + Copy + Eq,
+    T: Copy + PartialOrd + Sub<Output = T>,
 {
-    /// Check if the parameter change list contains any changes relevant to the subset
-    fn is_subscribed(parameter_change: &ParameterChangeList<Parameter, PARAMETER_COUNT>) -> bool;
+    let difference = if value > previous {
+        value - previous
+    } else {
+        previous - value
+    };
+
+    difference > threshold
+}
+
+/// A `Subset` that can be subscribed to by static subscribers, declared with
+/// `subscribers(...)` in the `#[subset(...)]` attribute. It is generated by the `Subset`
+/// proc-macro
+pub trait StaticSubset: Subset {
+    /// Storage of the subset static subscribers were last notified with. Only subsets with a
+    /// subscription filter store it, while all other subsets store nothing
+    type LastNotified: LastNotified<Self>;
+}
+
+/// The subset static subscribers were last notified with, used to filter out changes that
+/// doesn't pass the subscription filters of the subset
+pub trait LastNotified<S> {
+    /// Storage before any subscriber has been notified
+    const EMPTY: Self;
+
+    /// Check if static subscribers should be notified with `subset`
+    fn should_notify(&self, subset: &S) -> bool;
+
+    /// Store `subset` as the one the static subscribers were last notified with
+    fn replace(&self, subset: &S);
+}
+
+impl<S> LastNotified<S> for () {
+    const EMPTY: Self = ();
+
+    fn should_notify(&self, _subset: &S) -> bool {
+        true
+    }
+
+    fn replace(&self, _subset: &S) {}
+}
+
+impl<S: Subset> LastNotified<S> for RefCell<Option<S>> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY: Self = RefCell::new(None);
+
+    fn should_notify(&self, subset: &S) -> bool {
+        match self.borrow().as_ref() {
+            Some(last_notified) => subset.should_notify(last_notified),
+            None => true,
+        }
+    }
 
-    fn build_from_database(database: &dyn DatabaseRef<Parameter>) -> Self;
+    fn replace(&self, subset: &S) {
+        RefCell::replace(self, Some(subset.clone()));
+    }
 }
@@ -0,0 +1,153 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::{DatabaseSubscriber, NotifyProgress, Subset};
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(PanelDatabase)]
+#[subset(LampSubset)]
+#[subset(FanSubset)]
+struct PanelDatabaseContent {
+    lamp: u8,
+    fan: u8,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(PanelDatabase)]
+struct LampSubset {
+    lamp: u8,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(PanelDatabase)]
+struct FanSubset {
+    fan: u8,
+}
+
+#[test]
+fn budgeted_notify_resumes() {
+    let database = PanelDatabase::new(PanelDatabaseContent::default());
+    struct LampSubscriber {}
+    struct FanSubscriber {}
+
+    static LAMP_COUNT: AtomicUsize = AtomicUsize::new(0);
+    impl DatabaseSubscriber<LampSubset> for LampSubscriber {
+        fn on_set(&self, change: &LampSubset) {
+            LAMP_COUNT.fetch_add(change.lamp as usize, Ordering::SeqCst);
+        }
+    }
+
+    static FAN_COUNT: AtomicUsize = AtomicUsize::new(0);
+    impl DatabaseSubscriber<FanSubset> for FanSubscriber {
+        fn on_set(&self, change: &FanSubset) {
+            FAN_COUNT.fetch_add(change.fan as usize, Ordering::SeqCst);
+        }
+    }
+
+    let lamp_subscribers = [LampSubscriber {}, LampSubscriber {}];
+    let fan_subscriber = FanSubscriber {};
+
+    {
+        let handler_lock = database.get_subscriber_handler();
+        let mut handler = handler_lock.borrow_mut();
+        for subscriber in lamp_subscribers.iter() {
+            handler.subscribe_with_lamp_subset(subscriber).unwrap();
+        }
+        handler.subscribe_with_fan_subset(&fan_subscriber).unwrap();
+    }
+
+    database
+        .multi_set(&[PanelDatabaseMember::Lamp(1), PanelDatabaseMember::Fan(1)])
+        .unwrap();
+
+    // The budget stops the notification after one lamp subscriber
+    assert_eq!(
+        database.notify_subscribers_budgeted(1).unwrap(),
+        NotifyProgress::Pending
+    );
+    assert_eq!(LAMP_COUNT.load(Ordering::SeqCst), 1);
+    assert_eq!(FAN_COUNT.load(Ordering::SeqCst), 0);
+
+    // Changes made in between are notified after the ones in progress, which are notified with
+    // the values they were started with
+    database.set(&PanelDatabaseMember::Fan(10)).unwrap();
+    assert_eq!(
+        database.notify_subscribers_budgeted(2).unwrap(),
+        NotifyProgress::Pending
+    );
+    assert_eq!(LAMP_COUNT.load(Ordering::SeqCst), 2);
+    assert_eq!(FAN_COUNT.load(Ordering::SeqCst), 1);
+
+    // Each change is notified once
+    assert_eq!(
+        database.notify_subscribers_budgeted(2).unwrap(),
+        NotifyProgress::Complete
+    );
+    assert_eq!(LAMP_COUNT.load(Ordering::SeqCst), 2);
+    assert_eq!(FAN_COUNT.load(Ordering::SeqCst), 11);
+
+    assert_eq!(
+        database.notify_subscribers_budgeted(2).unwrap(),
+        NotifyProgress::Complete
+    );
+    assert_eq!(FAN_COUNT.load(Ordering::SeqCst), 11);
+
+    // Nothing to notify doesn't need the subscriber handler
+    let _handler_lock = database.get_subscriber_handler();
+    assert_eq!(
+        database.notify_subscribers_budgeted(2).unwrap(),
+        NotifyProgress::Complete
+    );
+}
+
+#[test]
+fn budget_spent_on_the_last_subscriber_completes() {
+    let database = PanelDatabase::new(PanelDatabaseContent::default());
+    struct LampSubscriber {}
+    struct FanSubscriber {}
+
+    static LAMP_COUNT: AtomicUsize = AtomicUsize::new(0);
+    impl DatabaseSubscriber<LampSubset> for LampSubscriber {
+        fn on_set(&self, _change: &LampSubset) {
+            LAMP_COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    static FAN_COUNT: AtomicUsize = AtomicUsize::new(0);
+    impl DatabaseSubscriber<FanSubset> for FanSubscriber {
+        fn on_set(&self, _change: &FanSubset) {
+            FAN_COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let lamp_subscriber = LampSubscriber {};
+    let fan_subscriber = FanSubscriber {};
+
+    {
+        let handler_lock = database.get_subscriber_handler();
+        let mut handler = handler_lock.borrow_mut();
+        handler
+            .subscribe_with_lamp_subset(&lamp_subscriber)
+            .unwrap();
+        handler.subscribe_with_fan_subset(&fan_subscriber).unwrap();
+    }
+
+    // Empty slots and unchanged subsets after the last subscriber don't need any budget
+    database.set(&PanelDatabaseMember::Lamp(1)).unwrap();
+    assert_eq!(
+        database.notify_subscribers_budgeted(1).unwrap(),
+        NotifyProgress::Complete
+    );
+    assert_eq!(LAMP_COUNT.load(Ordering::SeqCst), 1);
+
+    database
+        .multi_set(&[PanelDatabaseMember::Lamp(2), PanelDatabaseMember::Fan(2)])
+        .unwrap();
+    assert_eq!(
+        database.notify_subscribers_budgeted(2).unwrap(),
+        NotifyProgress::Complete
+    );
+    assert_eq!(LAMP_COUNT.load(Ordering::SeqCst), 2);
+    assert_eq!(FAN_COUNT.load(Ordering::SeqCst), 1);
+}
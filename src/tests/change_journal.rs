@@ -0,0 +1,77 @@
+use database_macro::Database;
+
+use crate::ChangeJournal;
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(AuditDatabase)]
+struct AuditDatabaseContent {
+    alice: u8,
+    bob: u16,
+}
+
+#[test]
+fn journal_keeps_overwritten_changes() {
+    let journal: ChangeJournal<AuditDatabaseMember, 8> = ChangeJournal::new();
+    let database =
+        AuditDatabase::new(AuditDatabaseContent::default()).with_change_journal(&journal);
+
+    database.set(&AuditDatabaseMember::Bob(1)).unwrap();
+    database.set(&AuditDatabaseMember::Bob(2)).unwrap();
+    database
+        .multi_set(&[AuditDatabaseMember::Alice(3), AuditDatabaseMember::Bob(4)])
+        .unwrap();
+
+    let mut entries = [None; 8];
+    let mut len = 0;
+    let overflowed = journal
+        .drain(|sequence, parameter| {
+            entries[len] = Some((sequence, parameter));
+            len += 1;
+        })
+        .unwrap();
+
+    assert_eq!(overflowed, 0);
+    assert_eq!(
+        entries[..len],
+        [
+            Some((0, AuditDatabaseMember::Bob(1))),
+            Some((1, AuditDatabaseMember::Bob(2))),
+            Some((2, AuditDatabaseMember::Alice(3))),
+            Some((2, AuditDatabaseMember::Bob(4))),
+        ]
+    );
+
+    // The journal is left empty
+    assert_eq!(journal.drain(|_, _| panic!()).unwrap(), 0);
+}
+
+#[test]
+fn full_journal_reports_overflow() {
+    let journal: ChangeJournal<AuditDatabaseMember, 2> = ChangeJournal::new();
+    let database =
+        AuditDatabase::new(AuditDatabaseContent::default()).with_change_journal(&journal);
+
+    for value in 0..5 {
+        database.set(&AuditDatabaseMember::Bob(value)).unwrap();
+    }
+
+    let mut sequences = [0; 2];
+    let mut len = 0;
+    let overflowed = journal
+        .drain(|sequence, _| {
+            sequences[len] = sequence;
+            len += 1;
+        })
+        .unwrap();
+    assert_eq!(overflowed, 3);
+    assert_eq!(sequences[..len], [0, 1]);
+
+    // Overflow is only reported once, and the journal accepts new entries after being drained
+    database.set(&AuditDatabaseMember::Bob(10)).unwrap();
+    let mut last = None;
+    let overflowed = journal
+        .drain(|sequence, parameter| last = Some((sequence, parameter)))
+        .unwrap();
+    assert_eq!(overflowed, 0);
+    assert_eq!(last, Some((5, AuditDatabaseMember::Bob(10))));
+}
@@ -0,0 +1,44 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::ChangeSignal;
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(SignalDatabase)]
+struct SignalDatabaseContent {
+    alice: u8,
+    bob: u16,
+}
+
+#[test]
+fn signal_on_first_change() {
+    struct WakeCounter {
+        count: AtomicUsize,
+    }
+
+    impl ChangeSignal for WakeCounter {
+        fn signal(&self) {
+            self.count.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let wake_counter = WakeCounter {
+        count: AtomicUsize::new(0),
+    };
+    let database =
+        SignalDatabase::new(SignalDatabaseContent::default()).with_change_signal(&wake_counter);
+
+    // Setting a parameter to its current value isn't a change
+    database.set(&SignalDatabaseMember::Alice(0)).unwrap();
+    assert_eq!(wake_counter.count.load(Ordering::SeqCst), 0);
+
+    // Only the first change is signaled until the changes are notified
+    database.set(&SignalDatabaseMember::Alice(1)).unwrap();
+    database.set(&SignalDatabaseMember::Bob(2)).unwrap();
+    assert_eq!(wake_counter.count.load(Ordering::SeqCst), 1);
+
+    database.notify_subscribers().unwrap();
+    database.set(&SignalDatabaseMember::Bob(3)).unwrap();
+    assert_eq!(wake_counter.count.load(Ordering::SeqCst), 2);
+}
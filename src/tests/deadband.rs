@@ -0,0 +1,67 @@
+use core::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::{DatabaseSubscriber, Subset};
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(SensorDatabase)]
+#[subset(SensorSubset)]
+struct SensorDatabaseContent {
+    alice: u8,
+    bob: u16,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(SensorDatabase)]
+#[deadband(bob = 10)]
+struct SensorSubset {
+    alice: u8,
+    bob: u16,
+}
+
+#[test]
+fn deadband_filters_small_changes() {
+    let database = SensorDatabase::new(SensorDatabaseContent::default());
+    struct SensorSubscriber {}
+
+    static TRIGGER_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static LAST_BOB: AtomicU16 = AtomicU16::new(0);
+    impl DatabaseSubscriber<SensorSubset> for SensorSubscriber {
+        fn on_set(&self, change: &SensorSubset) {
+            TRIGGER_COUNT.fetch_add(1, Ordering::SeqCst);
+            LAST_BOB.store(change.bob, Ordering::SeqCst);
+        }
+    }
+
+    let subscriber = SensorSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_sensor_subset(&subscriber)
+        .unwrap();
+
+    // The first change is always notified
+    database.set(&SensorDatabaseMember::Bob(100)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+
+    // Jitter within the deadband is filtered out, also when it accumulates
+    for value in [105, 95, 110, 90] {
+        database.set(&SensorDatabaseMember::Bob(value)).unwrap();
+        database.notify_subscribers().unwrap();
+    }
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+
+    // Moving past the threshold from the last notified value triggers a notification
+    database.set(&SensorDatabaseMember::Bob(89)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 2);
+    assert_eq!(LAST_BOB.load(Ordering::SeqCst), 89);
+
+    // Fields without a deadband notify on any change
+    database.set(&SensorDatabaseMember::Alice(1)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 3);
+}
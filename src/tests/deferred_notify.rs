@@ -0,0 +1,317 @@
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use database_macro::Database;
+
+use crate::{DatabaseSubscriber, NotifyOutcome, NotifyPolicy, NotifyProgress, Subset};
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(RelayDatabase)]
+#[subset(InputSubset)]
+#[subset(OutputSubset)]
+#[subset(TriggerSubset, group = fast)]
+struct RelayDatabaseContent {
+    input: u8,
+    output: u8,
+    trigger: u8,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(RelayDatabase)]
+struct InputSubset {
+    input: u8,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(RelayDatabase)]
+struct OutputSubset {
+    output: u8,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(RelayDatabase)]
+struct TriggerSubset {
+    trigger: u8,
+}
+
+#[test]
+fn notify_is_deferred_to_the_notifying_context() {
+    let database = RelayDatabase::new(RelayDatabaseContent::default());
+
+    // Sets and notifies the database while it's being notified, as an interrupt would
+    struct Relay<'d> {
+        database: &'d RelayDatabase<'d>,
+    }
+
+    impl DatabaseSubscriber<InputSubset> for Relay<'_> {
+        fn on_set(&self, change: &InputSubset) {
+            self.database
+                .set(&RelayDatabaseMember::Output(change.input * 2))
+                .unwrap();
+            assert_eq!(
+                self.database.notify_subscribers_or_defer(),
+                NotifyOutcome::Deferred
+            );
+        }
+    }
+
+    struct OutputSubscriber {}
+
+    static LAST_OUTPUT: AtomicU8 = AtomicU8::new(0);
+    impl DatabaseSubscriber<OutputSubset> for OutputSubscriber {
+        fn on_set(&self, change: &OutputSubset) {
+            LAST_OUTPUT.store(change.output, Ordering::SeqCst);
+        }
+    }
+
+    let relay = Relay {
+        database: &database,
+    };
+    let output_subscriber = OutputSubscriber {};
+
+    {
+        let handler_lock = database.get_subscriber_handler();
+        let mut handler = handler_lock.borrow_mut();
+        handler.subscribe_with_input_subset(&relay).unwrap();
+        handler
+            .subscribe_with_output_subset(&output_subscriber)
+            .unwrap();
+    }
+
+    // The output is set after the output subset was checked, and is notified before returning
+    database.set(&RelayDatabaseMember::Input(3)).unwrap();
+    assert_eq!(
+        database.notify_subscribers_or_defer(),
+        NotifyOutcome::Inline
+    );
+    assert_eq!(LAST_OUTPUT.load(Ordering::SeqCst), 6);
+}
+
+#[test]
+fn notify_is_deferred_while_polling() {
+    let database = RelayDatabase::new(RelayDatabaseContent::default());
+
+    // Sets and notifies the database while it's being polled
+    struct Relay<'d> {
+        database: &'d RelayDatabase<'d>,
+    }
+
+    impl DatabaseSubscriber<InputSubset> for Relay<'_> {
+        fn on_set(&self, change: &InputSubset) {
+            self.database
+                .set(&RelayDatabaseMember::Output(change.input * 2))
+                .unwrap();
+            assert_eq!(
+                self.database.notify_subscribers_or_defer(),
+                NotifyOutcome::Deferred
+            );
+        }
+    }
+
+    struct OutputSubscriber {}
+
+    static LAST_OUTPUT: AtomicU8 = AtomicU8::new(0);
+    impl DatabaseSubscriber<OutputSubset> for OutputSubscriber {
+        fn on_set(&self, change: &OutputSubset) {
+            LAST_OUTPUT.store(change.output, Ordering::SeqCst);
+        }
+    }
+
+    let relay = Relay {
+        database: &database,
+    };
+    let output_subscriber = OutputSubscriber {};
+
+    {
+        let handler_lock = database.get_subscriber_handler();
+        let mut handler = handler_lock.borrow_mut();
+        handler
+            .subscribe_with_input_subset_policy(&relay, NotifyPolicy::Debounce(10))
+            .unwrap();
+        handler
+            .subscribe_with_output_subset(&output_subscriber)
+            .unwrap();
+    }
+
+    // The relay is notified by the poll, and the output it sets is notified before returning
+    database.set(&RelayDatabaseMember::Input(4)).unwrap();
+    database.notify_subscribers().unwrap();
+    database.poll(0u64).unwrap();
+    assert_eq!(LAST_OUTPUT.load(Ordering::SeqCst), 0);
+    database.poll(20u64).unwrap();
+    assert_eq!(LAST_OUTPUT.load(Ordering::SeqCst), 8);
+}
+
+#[test]
+fn notify_is_not_deferred_by_groups() {
+    let database = RelayDatabase::new(RelayDatabaseContent::default());
+
+    // Sets and notifies the database while a group is being notified
+    struct Trigger<'d> {
+        database: &'d RelayDatabase<'d>,
+    }
+
+    impl DatabaseSubscriber<TriggerSubset> for Trigger<'_> {
+        fn on_set(&self, change: &TriggerSubset) {
+            self.database
+                .set(&RelayDatabaseMember::Output(change.trigger + 1))
+                .unwrap();
+            assert_eq!(
+                self.database.notify_subscribers_or_defer(),
+                NotifyOutcome::Inline
+            );
+        }
+    }
+
+    struct OutputSubscriber {}
+
+    static LAST_OUTPUT: AtomicU8 = AtomicU8::new(0);
+    impl DatabaseSubscriber<OutputSubset> for OutputSubscriber {
+        fn on_set(&self, change: &OutputSubset) {
+            LAST_OUTPUT.store(change.output, Ordering::SeqCst);
+        }
+    }
+
+    let trigger = Trigger {
+        database: &database,
+    };
+    let output_subscriber = OutputSubscriber {};
+
+    database
+        .get_group_handler()
+        .subscribe_with_trigger_subset(&trigger)
+        .unwrap();
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_output_subset(&output_subscriber)
+        .unwrap();
+
+    // Groups don't lock the subscriber handler, such that the output is notified right away
+    database.set(&RelayDatabaseMember::Trigger(4)).unwrap();
+    database.notify_group(RelayDatabaseGroup::Fast).unwrap();
+    assert_eq!(LAST_OUTPUT.load(Ordering::SeqCst), 5);
+}
+
+#[test]
+fn deferred_notify_outlives_the_budget() {
+    let database = RelayDatabase::new(RelayDatabaseContent::default());
+
+    struct Relay<'d> {
+        database: &'d RelayDatabase<'d>,
+    }
+
+    impl DatabaseSubscriber<InputSubset> for Relay<'_> {
+        fn on_set(&self, change: &InputSubset) {
+            self.database
+                .set(&RelayDatabaseMember::Output(change.input * 3))
+                .unwrap();
+            assert_eq!(
+                self.database.notify_subscribers_or_defer(),
+                NotifyOutcome::Deferred
+            );
+        }
+    }
+
+    struct OutputSubscriber {}
+
+    static LAST_OUTPUT: AtomicU8 = AtomicU8::new(0);
+    impl DatabaseSubscriber<OutputSubset> for OutputSubscriber {
+        fn on_set(&self, change: &OutputSubset) {
+            LAST_OUTPUT.store(change.output, Ordering::SeqCst);
+        }
+    }
+
+    let relay = Relay {
+        database: &database,
+    };
+    let output_subscriber = OutputSubscriber {};
+
+    {
+        let handler_lock = database.get_subscriber_handler();
+        let mut handler = handler_lock.borrow_mut();
+        handler.subscribe_with_input_subset(&relay).unwrap();
+        handler
+            .subscribe_with_output_subset(&output_subscriber)
+            .unwrap();
+    }
+
+    // The budget runs out before the deferred notification is made, which is told by the progress
+    database.set(&RelayDatabaseMember::Input(2)).unwrap();
+    assert_eq!(
+        database.notify_subscribers_budgeted(1).unwrap(),
+        NotifyProgress::Deferred
+    );
+    assert_eq!(LAST_OUTPUT.load(Ordering::SeqCst), 0);
+
+    // The next context releasing the subscriber handler makes the deferred notification
+    database.poll(0u64).unwrap();
+    assert_eq!(LAST_OUTPUT.load(Ordering::SeqCst), 6);
+}
+
+#[test]
+fn notify_with_nothing_changed_is_not_deferred() {
+    let database = RelayDatabase::new(RelayDatabaseContent::default());
+
+    struct OutputSubscriber {}
+
+    static LAST_OUTPUT: AtomicU8 = AtomicU8::new(0);
+    impl DatabaseSubscriber<OutputSubset> for OutputSubscriber {
+        fn on_set(&self, change: &OutputSubset) {
+            LAST_OUTPUT.store(change.output, Ordering::SeqCst);
+        }
+    }
+
+    let output_subscriber = OutputSubscriber {};
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_output_subset(&output_subscriber)
+        .unwrap();
+
+    assert_eq!(
+        database.notify_subscribers_or_defer(),
+        NotifyOutcome::Inline
+    );
+
+    // Nothing is left for the next context releasing the subscriber handler to notify, such that
+    // changes are only notified when asked for
+    database.set(&RelayDatabaseMember::Output(1)).unwrap();
+    database.poll(0u64).unwrap();
+    assert_eq!(LAST_OUTPUT.load(Ordering::SeqCst), 0);
+    database.notify_subscribers().unwrap();
+    assert_eq!(LAST_OUTPUT.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn notify_is_deferred_to_the_subscriber_handler_lock() {
+    let database = RelayDatabase::new(RelayDatabaseContent::default());
+
+    struct OutputSubscriber {}
+
+    static LAST_OUTPUT: AtomicU8 = AtomicU8::new(0);
+    impl DatabaseSubscriber<OutputSubset> for OutputSubscriber {
+        fn on_set(&self, change: &OutputSubset) {
+            LAST_OUTPUT.store(change.output, Ordering::SeqCst);
+        }
+    }
+
+    let output_subscriber = OutputSubscriber {};
+
+    // The output is set while the subscriber handler is locked by the user, and is notified when
+    // the lock is dropped
+    {
+        let handler_lock = database.get_subscriber_handler();
+        handler_lock
+            .borrow_mut()
+            .subscribe_with_output_subset(&output_subscriber)
+            .unwrap();
+
+        database.set(&RelayDatabaseMember::Output(7)).unwrap();
+        assert_eq!(
+            database.notify_subscribers_or_defer(),
+            NotifyOutcome::Deferred
+        );
+        assert_eq!(LAST_OUTPUT.load(Ordering::SeqCst), 0);
+    }
+    assert_eq!(LAST_OUTPUT.load(Ordering::SeqCst), 7);
+}
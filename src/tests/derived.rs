@@ -0,0 +1,127 @@
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::{DatabaseError, DatabaseSubscriber, Subset};
+
+fn compute_power(voltage: u16, current: u16) -> u32 {
+    voltage as u32 * current as u32
+}
+
+fn compute_doubled_power(power: u32) -> u32 {
+    power * 2
+}
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(PowerDatabase)]
+#[subset(PowerSubset)]
+struct PowerDatabaseContent {
+    voltage: u16,
+    current: u16,
+    #[derived(inputs(voltage, current), with = compute_power)]
+    power: u32,
+    #[derived(inputs(power), with = compute_doubled_power)]
+    doubled_power: u32,
+    alice: u8,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(PowerDatabase)]
+struct PowerSubset {
+    power: u32,
+    doubled_power: u32,
+}
+
+#[test]
+fn derived_parameters_are_recomputed() {
+    let database = PowerDatabase::new(PowerDatabaseContent::default());
+    struct PowerSubscriber {}
+
+    static TRIGGER_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static LAST_POWER: AtomicU32 = AtomicU32::new(0);
+    impl DatabaseSubscriber<PowerSubset> for PowerSubscriber {
+        fn on_set(&self, change: &PowerSubset) {
+            TRIGGER_COUNT.fetch_add(1, Ordering::SeqCst);
+            LAST_POWER.store(change.power, Ordering::SeqCst);
+            assert_eq!(change.doubled_power, change.power * 2);
+        }
+    }
+
+    let subscriber = PowerSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_power_subset(&subscriber)
+        .unwrap();
+
+    database
+        .multi_set(&[
+            PowerDatabaseMember::Voltage(12),
+            PowerDatabaseMember::Current(3),
+        ])
+        .unwrap();
+    assert_eq!(
+        database.get(&PowerDatabaseMember::Power(0)),
+        PowerDatabaseMember::Power(36)
+    );
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+    assert_eq!(LAST_POWER.load(Ordering::SeqCst), 36);
+
+    // Changing a parameter that isn't an input leaves the derived parameters untouched
+    database.set(&PowerDatabaseMember::Alice(1)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+
+    // Changing the inputs without changing the derived parameters isn't notified
+    database
+        .multi_set(&[
+            PowerDatabaseMember::Voltage(4),
+            PowerDatabaseMember::Current(9),
+        ])
+        .unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn derived_parameters_cant_be_set() {
+    let database = PowerDatabase::new(PowerDatabaseContent::default());
+    assert!(matches!(
+        database.multi_set(&[
+            PowerDatabaseMember::Voltage(2),
+            PowerDatabaseMember::Power(4),
+        ]),
+        Err(DatabaseError::DerivedParameter)
+    ));
+
+    // None of the parameters are set
+    assert_eq!(
+        database.get(&PowerDatabaseMember::Voltage(0)),
+        PowerDatabaseMember::Voltage(0)
+    );
+    assert_eq!(
+        database.get(&PowerDatabaseMember::Power(0)),
+        PowerDatabaseMember::Power(0)
+    );
+}
+
+#[test]
+fn derived_parameters_are_computed_on_creation() {
+    let database = PowerDatabase::new(PowerDatabaseContent {
+        voltage: 3,
+        current: 4,
+        ..Default::default()
+    });
+
+    // Derived parameters are consistent with their inputs before anything is set
+    assert!(matches!(
+        database.get(&PowerDatabaseMember::Power(0)),
+        PowerDatabaseMember::Power(12)
+    ));
+    assert!(matches!(
+        database.get(&PowerDatabaseMember::DoubledPower(0)),
+        PowerDatabaseMember::DoubledPower(24)
+    ));
+}
@@ -0,0 +1,84 @@
+use core::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::{DatabaseRef, DynamicSubscriber, KeyMask};
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(PluginDatabase)]
+struct PluginDatabaseContent {
+    alice: u8,
+    bob: u16,
+    debbie: u16,
+}
+
+#[test]
+fn dynamic_subscribers_see_masked_changes() {
+    let database = PluginDatabase::new(PluginDatabaseContent::default());
+    struct Plugin {}
+
+    static CALL_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static CHANGE_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static LAST_DEBBIE: AtomicU16 = AtomicU16::new(0);
+    impl DynamicSubscriber<PluginDatabaseMember> for Plugin {
+        fn on_change(
+            &self,
+            changed: &mut dyn Iterator<Item = &PluginDatabaseMember>,
+            database: &dyn DatabaseRef<PluginDatabaseMember>,
+        ) {
+            CALL_COUNT.fetch_add(1, Ordering::SeqCst);
+            CHANGE_COUNT.fetch_add(changed.count(), Ordering::SeqCst);
+            if let PluginDatabaseMember::Debbie(value) =
+                database.internal_get(&PluginDatabaseMember::Debbie(0))
+            {
+                LAST_DEBBIE.store(value, Ordering::SeqCst);
+            }
+        }
+    }
+
+    let plugin = Plugin {};
+    let keys =
+        PluginDatabaseKeyMask::from_keys(&[PluginDatabaseKey::Bob, PluginDatabaseKey::Debbie]);
+    assert!(!keys.contains(PluginDatabaseKey::Alice));
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_dynamic(keys, &plugin)
+        .unwrap();
+
+    // Changes outside of the mask aren't notified
+    database.set(&PluginDatabaseMember::Alice(1)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 0);
+
+    // The subscriber is called once, with only the changes in the mask
+    database
+        .multi_set(&[
+            PluginDatabaseMember::Alice(2),
+            PluginDatabaseMember::Bob(3),
+            PluginDatabaseMember::Debbie(4),
+        ])
+        .unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 1);
+    assert_eq!(CHANGE_COUNT.load(Ordering::SeqCst), 2);
+    assert_eq!(LAST_DEBBIE.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn key_masks_span_multiple_words() {
+    #[derive(Clone, Copy)]
+    struct Position(usize);
+    impl From<Position> for usize {
+        fn from(position: Position) -> usize {
+            position.0
+        }
+    }
+
+    let keys = KeyMask::<Position, 2>::from_keys(&[Position(1), Position(100)]);
+    assert!(keys.contains(Position(1)));
+    assert!(keys.contains(Position(100)));
+    assert!(!keys.contains(Position(36)));
+    assert!(!keys.contains(Position(65)));
+}
@@ -0,0 +1,71 @@
+use core::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::FieldSubscriber;
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(MixerDatabase)]
+struct MixerDatabaseContent {
+    alice: u8,
+    #[subscribable]
+    bob: u16,
+    #[indexed]
+    #[subscribable]
+    gains: [u16; 4],
+}
+
+#[test]
+fn field_subscribers_are_notified_with_values() {
+    let database = MixerDatabase::new(MixerDatabaseContent::default());
+    struct BobSubscriber {}
+    struct GainSubscriber {}
+
+    static BOB_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static LAST_BOB: AtomicU16 = AtomicU16::new(0);
+    impl FieldSubscriber<u16> for BobSubscriber {
+        fn on_field_change(&self, value: &u16) {
+            BOB_COUNT.fetch_add(1, Ordering::SeqCst);
+            LAST_BOB.store(*value, Ordering::SeqCst);
+        }
+    }
+
+    static GAIN_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static LAST_GAIN: AtomicU16 = AtomicU16::new(0);
+    impl FieldSubscriber<(usize, u16)> for GainSubscriber {
+        fn on_field_change(&self, (index, gain): &(usize, u16)) {
+            GAIN_COUNT.fetch_add(1, Ordering::SeqCst);
+            LAST_GAIN.store(*index as u16 * 100 + *gain, Ordering::SeqCst);
+        }
+    }
+
+    let bob_subscriber = BobSubscriber {};
+    let gain_subscriber = GainSubscriber {};
+
+    {
+        let handler = database.get_subscriber_handler();
+        let mut handler = handler.borrow_mut();
+        handler.subscribe_to_bob(&bob_subscriber).unwrap();
+        handler.subscribe_to_gains(&gain_subscriber).unwrap();
+    }
+
+    // Changes to other fields aren't notified to the field subscribers
+    database.set(&MixerDatabaseMember::Alice(1)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(BOB_COUNT.load(Ordering::SeqCst), 0);
+    assert_eq!(GAIN_COUNT.load(Ordering::SeqCst), 0);
+
+    database
+        .multi_set(&[
+            MixerDatabaseMember::Bob(5),
+            MixerDatabaseMember::Gains(2, 7),
+        ])
+        .unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(BOB_COUNT.load(Ordering::SeqCst), 1);
+    assert_eq!(LAST_BOB.load(Ordering::SeqCst), 5);
+
+    // Elements of indexed fields are notified with their index
+    assert_eq!(GAIN_COUNT.load(Ordering::SeqCst), 1);
+    assert_eq!(LAST_GAIN.load(Ordering::SeqCst), 207);
+}
@@ -0,0 +1,130 @@
+use core::sync::atomic::{AtomicI16, AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::{DatabaseSubscriber, FieldSubscriber, Subset};
+
+mod groups {
+    use database_macro::Database;
+
+    // Content structs marked as groups can only be flattened into databases
+    #[derive(Database, Default, Clone, Copy)]
+    #[group]
+    pub struct SensorConfig {
+        #[subscribable]
+        pub offset: i16,
+    }
+
+    #[derive(Database, Default, Clone, Copy)]
+    #[group]
+    pub struct MotorConfig {
+        pub speed: u16,
+        #[history(4)]
+        pub current: u16,
+        #[flatten]
+        pub sensor: SensorConfig,
+    }
+}
+
+use groups::MotorConfig;
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(MachineDatabase)]
+#[subset(MotorSpeedSubset)]
+struct MachineDatabaseContent {
+    alice: u8,
+    #[flatten]
+    motor: MotorConfig,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(MachineDatabase)]
+struct MotorSpeedSubset {
+    motor_speed: u16,
+}
+
+#[test]
+fn flattened_members_are_tracked_per_leaf() {
+    let database = MachineDatabase::new(MachineDatabaseContent::default());
+    struct MotorSpeedSubscriber {}
+
+    static TRIGGER_COUNT: AtomicUsize = AtomicUsize::new(0);
+    impl DatabaseSubscriber<MotorSpeedSubset> for MotorSpeedSubscriber {
+        fn on_set(&self, change: &MotorSpeedSubset) {
+            TRIGGER_COUNT.fetch_add(1, Ordering::SeqCst);
+            assert_eq!(change.motor_speed, 1200);
+        }
+    }
+
+    let subscriber = MotorSpeedSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_motor_speed_subset(&subscriber)
+        .unwrap();
+
+    assert_eq!(MACHINE_DATABASE_MEMBER_COUNT, 4);
+
+    // Changing another member of the same group doesn't notify the subset
+    database
+        .set(&MachineDatabaseMember::MotorCurrent(3))
+        .unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 0);
+
+    database
+        .set(&MachineDatabaseMember::MotorSpeed(1200))
+        .unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+
+    database
+        .set(&MachineDatabaseMember::MotorSensorOffset(-2))
+        .unwrap();
+    assert_eq!(
+        database.get(&MachineDatabaseMember::MotorSensorOffset(0)),
+        MachineDatabaseMember::MotorSensorOffset(-2)
+    );
+    assert_eq!(
+        database.with_history_of_motor_current(|history| history.len()),
+        1
+    );
+}
+
+#[test]
+fn flattened_members_have_hierarchical_names() {
+    assert_eq!(MachineDatabaseMember::Alice(0).name(), "alice");
+    assert_eq!(MachineDatabaseMember::MotorSpeed(0).name(), "motor.speed");
+    assert_eq!(
+        MachineDatabaseMember::MotorSensorOffset(0).name(),
+        "motor.sensor.offset"
+    );
+}
+
+#[test]
+fn flattened_members_keep_their_attributes() {
+    let database = MachineDatabase::new(MachineDatabaseContent::default());
+    struct OffsetSubscriber {}
+
+    static LAST_OFFSET: AtomicI16 = AtomicI16::new(0);
+    impl FieldSubscriber<i16> for OffsetSubscriber {
+        fn on_field_change(&self, value: &i16) {
+            LAST_OFFSET.store(*value, Ordering::SeqCst);
+        }
+    }
+
+    let subscriber = OffsetSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_to_motor_sensor_offset(&subscriber)
+        .unwrap();
+
+    database
+        .set(&MachineDatabaseMember::MotorSensorOffset(-7))
+        .unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(LAST_OFFSET.load(Ordering::SeqCst), -7);
+}
@@ -0,0 +1,152 @@
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::{DatabaseSubscriber, Subset};
+
+// Comparisons are resolved through the `Float` trait, such that aliases of floats are compared too
+type Celsius = f32;
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(ClimateDatabase)]
+#[subset(ClimateSubset)]
+#[subset(TemperatureSubset)]
+struct ClimateDatabaseContent {
+    #[compare(bitwise)]
+    temperature: Celsius,
+    #[compare(epsilon = 0.5)]
+    humidity: f32,
+    #[compare(nan_aware)]
+    pressure: f64,
+    setpoint: f32,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(ClimateDatabase)]
+struct ClimateSubset {
+    temperature: f32,
+    humidity: f32,
+    pressure: f64,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(ClimateDatabase)]
+struct TemperatureSubset {
+    temperature: Celsius,
+}
+
+#[test]
+fn floats_are_compared_per_member() {
+    let database = ClimateDatabase::new(ClimateDatabaseContent::default());
+    struct ClimateSubscriber {}
+
+    static TRIGGER_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static LAST_HUMIDITY: AtomicU32 = AtomicU32::new(0);
+    impl DatabaseSubscriber<ClimateSubset> for ClimateSubscriber {
+        fn on_set(&self, change: &ClimateSubset) {
+            TRIGGER_COUNT.fetch_add(1, Ordering::SeqCst);
+            LAST_HUMIDITY.store(change.humidity.to_bits(), Ordering::SeqCst);
+            assert!(change.temperature.is_nan() || change.temperature == 0.0);
+            assert!(change.pressure.is_nan() || change.pressure == 0.0);
+        }
+    }
+
+    let subscriber = ClimateSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_climate_subset(&subscriber)
+        .unwrap();
+
+    let notified = |parameter: ClimateDatabaseMember| {
+        let count = TRIGGER_COUNT.load(Ordering::SeqCst);
+        database.set(&parameter).unwrap();
+        database.notify_subscribers().unwrap();
+        TRIGGER_COUNT.load(Ordering::SeqCst) > count
+    };
+
+    // Bitwise compared floats doesn't see setting the same NaN again as a change
+    assert!(notified(ClimateDatabaseMember::Temperature(f32::NAN)));
+    assert!(!notified(ClimateDatabaseMember::Temperature(f32::NAN)));
+    assert!(notified(ClimateDatabaseMember::Temperature(0.0)));
+    assert!(notified(ClimateDatabaseMember::Temperature(-0.0)));
+
+    // Changes within the epsilon aren't notified, and are measured from the last change such that
+    // small changes can't drift unnoticed
+    assert!(!notified(ClimateDatabaseMember::Humidity(0.25)));
+    assert!(matches!(
+        database.get(&ClimateDatabaseMember::Humidity(0.0)),
+        ClimateDatabaseMember::Humidity(0.25)
+    ));
+    assert!(!notified(ClimateDatabaseMember::Humidity(0.5)));
+    assert!(notified(ClimateDatabaseMember::Humidity(0.75)));
+    assert_eq!(f32::from_bits(LAST_HUMIDITY.load(Ordering::SeqCst)), 0.75);
+    assert!(!notified(ClimateDatabaseMember::Humidity(1.0)));
+    assert!(notified(ClimateDatabaseMember::Humidity(1.5)));
+
+    // NaN-aware members treat all NaNs as equal, as well as both zeroes
+    assert!(notified(ClimateDatabaseMember::Pressure(f64::NAN)));
+    assert!(!notified(ClimateDatabaseMember::Pressure(-f64::NAN)));
+    assert!(matches!(
+        database.get(&ClimateDatabaseMember::Pressure(0.0)),
+        ClimateDatabaseMember::Pressure(pressure) if pressure.is_nan()
+    ));
+    assert!(notified(ClimateDatabaseMember::Pressure(0.0)));
+    assert!(!notified(ClimateDatabaseMember::Pressure(-0.0)));
+}
+
+#[test]
+fn unchanged_members_are_not_notified() {
+    let database = ClimateDatabase::new(ClimateDatabaseContent::default());
+    struct TemperatureSubscriber {}
+
+    static TRIGGER_COUNT: AtomicUsize = AtomicUsize::new(0);
+    impl DatabaseSubscriber<TemperatureSubset> for TemperatureSubscriber {
+        fn on_set(&self, change: &TemperatureSubset) {
+            TRIGGER_COUNT.fetch_add(1, Ordering::SeqCst);
+            assert_eq!(change.temperature, 21.0);
+        }
+    }
+
+    let subscriber = TemperatureSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_temperature_subset(&subscriber)
+        .unwrap();
+
+    // Setting a member to its current value along with a change to another member doesn't notify
+    // the subsets of the unchanged member
+    database
+        .set(&ClimateDatabaseMember::Temperature(21.0))
+        .unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+
+    database
+        .multi_set(&[
+            ClimateDatabaseMember::Temperature(21.0),
+            ClimateDatabaseMember::Pressure(1.0),
+        ])
+        .unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn floats_are_compared_bitwise_by_default() {
+    let database = ClimateDatabase::new(ClimateDatabaseContent::default());
+    let changed = |parameter: ClimateDatabaseMember| {
+        let version = database.version();
+        database.set(&parameter).unwrap();
+        database.version() > version
+    };
+
+    // Floats without a comparison don't see the same NaN as a change, but do see a change of sign
+    assert!(changed(ClimateDatabaseMember::Setpoint(f32::NAN)));
+    assert!(!changed(ClimateDatabaseMember::Setpoint(f32::NAN)));
+    assert!(changed(ClimateDatabaseMember::Setpoint(0.0)));
+    assert!(changed(ClimateDatabaseMember::Setpoint(-0.0)));
+}
@@ -0,0 +1,107 @@
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::{DatabaseSubscriber, Subset};
+
+trait Sensor: Copy + Eq + Default {
+    fn raw(&self) -> u32;
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+struct Thermometer(u32);
+
+impl Sensor for Thermometer {
+    fn raw(&self) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Database, Clone, Copy)]
+#[name(BoardDatabase)]
+#[subset(BoardSubset<SAMPLES, T>)]
+struct BoardDatabaseContent<T: Sensor, const SAMPLES: usize> {
+    reading: T,
+    samples: [u8; SAMPLES],
+    #[history(2)]
+    enabled: bool,
+}
+
+// The generics of the subset are mapped onto the generics of the database by the superset
+#[derive(Subset, Clone, Copy)]
+#[superset(BoardDatabase<S, N>)]
+struct BoardSubset<const N: usize, S: Sensor> {
+    reading: S,
+    samples: [u8; N],
+}
+
+#[derive(Database, Clone, Copy)]
+#[name(LabelDatabase)]
+struct LabelDatabaseContent<'b> {
+    label: &'b str,
+}
+
+#[test]
+fn generic_content() {
+    let database = BoardDatabase::new(BoardDatabaseContent {
+        reading: Thermometer(0),
+        samples: [0; 3],
+        enabled: false,
+    });
+    struct BoardSubscriber {}
+
+    static TRIGGER_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static LAST_READING: AtomicU32 = AtomicU32::new(0);
+    static LAST_SAMPLE: AtomicU32 = AtomicU32::new(0);
+    impl<const N: usize, S: Sensor> DatabaseSubscriber<BoardSubset<N, S>> for BoardSubscriber {
+        fn on_set(&self, change: &BoardSubset<N, S>) {
+            TRIGGER_COUNT.fetch_add(1, Ordering::SeqCst);
+            LAST_READING.store(change.reading.raw(), Ordering::SeqCst);
+            LAST_SAMPLE.store(change.samples[N - 1] as u32, Ordering::SeqCst);
+        }
+    }
+
+    let subscriber = BoardSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_board_subset(&subscriber)
+        .unwrap();
+
+    database.set(&BoardDatabaseMember::Enabled(true)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 0);
+
+    database
+        .set(&BoardDatabaseMember::Reading(Thermometer(21)))
+        .unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+    assert_eq!(LAST_READING.load(Ordering::SeqCst), 21);
+
+    database
+        .set(&BoardDatabaseMember::Samples([1, 2, 3]))
+        .unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 2);
+    assert_eq!(LAST_SAMPLE.load(Ordering::SeqCst), 3);
+
+    assert_eq!(
+        database.get(&BoardDatabaseMember::Samples([0; 3])),
+        BoardDatabaseMember::Samples([1, 2, 3])
+    );
+    assert_eq!(database.with_history_of_enabled(|history| history.len()), 1);
+}
+
+#[test]
+fn borrowed_content() {
+    let label = "board";
+    let database = LabelDatabase::new(LabelDatabaseContent { label: "unnamed" });
+
+    database.set(&LabelDatabaseMember::Label(label)).unwrap();
+    assert_eq!(
+        database.get(&LabelDatabaseMember::Label("")),
+        LabelDatabaseMember::Label("board")
+    );
+}
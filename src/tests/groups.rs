@@ -0,0 +1,170 @@
+use core::sync::atomic::{AtomicBool, AtomicI16, AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::{DatabaseError, DatabaseSubscriber, Subset};
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(DriveDatabase)]
+#[subset(TorqueSubset, group = fast)]
+#[subset(StatusSubset, group = slow)]
+#[subset(AllSubset)]
+struct DriveDatabaseContent {
+    torque: i16,
+    status: u8,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(DriveDatabase)]
+struct TorqueSubset {
+    torque: i16,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(DriveDatabase)]
+struct StatusSubset {
+    torque: i16,
+    status: u8,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(DriveDatabase)]
+struct AllSubset {
+    torque: i16,
+    status: u8,
+}
+
+#[test]
+fn groups_are_notified_separately() {
+    let database = DriveDatabase::new(DriveDatabaseContent::default());
+    struct ControlLoop {}
+    struct Display {}
+    struct Logger {}
+
+    static LAST_TORQUE: AtomicI16 = AtomicI16::new(0);
+    impl DatabaseSubscriber<TorqueSubset> for ControlLoop {
+        fn on_set(&self, change: &TorqueSubset) {
+            LAST_TORQUE.store(change.torque, Ordering::SeqCst);
+        }
+    }
+
+    static DISPLAY_COUNT: AtomicUsize = AtomicUsize::new(0);
+    impl DatabaseSubscriber<StatusSubset> for Display {
+        fn on_set(&self, change: &StatusSubset) {
+            DISPLAY_COUNT.fetch_add(1, Ordering::SeqCst);
+            assert_eq!((change.torque, change.status), (50, 0));
+        }
+    }
+
+    static LOGGER_COUNT: AtomicUsize = AtomicUsize::new(0);
+    impl DatabaseSubscriber<AllSubset> for Logger {
+        fn on_set(&self, change: &AllSubset) {
+            LOGGER_COUNT.fetch_add(1, Ordering::SeqCst);
+            assert_eq!((change.torque, change.status), (50, 0));
+        }
+    }
+
+    let control_loop = ControlLoop {};
+    let display = Display {};
+    let logger = Logger {};
+
+    // Subsets in groups are subscribed to through the group handler
+    let group_handler = database.get_group_handler();
+    group_handler
+        .subscribe_with_torque_subset(&control_loop)
+        .unwrap();
+    group_handler
+        .subscribe_with_status_subset(&display)
+        .unwrap();
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_all_subset(&logger)
+        .unwrap();
+
+    database.set(&DriveDatabaseMember::Torque(40)).unwrap();
+
+    // Only the fast group is notified, and the change is kept for the others
+    database.notify_group(DriveDatabaseGroup::Fast).unwrap();
+    assert_eq!(LAST_TORQUE.load(Ordering::SeqCst), 40);
+    assert_eq!(DISPLAY_COUNT.load(Ordering::SeqCst), 0);
+    assert_eq!(LOGGER_COUNT.load(Ordering::SeqCst), 0);
+
+    database.set(&DriveDatabaseMember::Torque(50)).unwrap();
+    database.notify_group(DriveDatabaseGroup::Fast).unwrap();
+    assert_eq!(LAST_TORQUE.load(Ordering::SeqCst), 50);
+
+    // The slow group sees both changes at once
+    database.notify_group(DriveDatabaseGroup::Slow).unwrap();
+    assert_eq!(DISPLAY_COUNT.load(Ordering::SeqCst), 1);
+    database.notify_group(DriveDatabaseGroup::Slow).unwrap();
+    assert_eq!(DISPLAY_COUNT.load(Ordering::SeqCst), 1);
+
+    // Subsets outside of groups are notified as before
+    database.notify_subscribers().unwrap();
+    assert_eq!(LOGGER_COUNT.load(Ordering::SeqCst), 1);
+    assert_eq!(DISPLAY_COUNT.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn groups_are_locked_separately() {
+    let database = DriveDatabase::new(DriveDatabaseContent::default());
+    struct ControlLoop {}
+    struct Display<'a> {
+        database: &'a DriveDatabase<'a>,
+    }
+
+    static CONTROL_LOOP_COUNT: AtomicUsize = AtomicUsize::new(0);
+    impl DatabaseSubscriber<TorqueSubset> for ControlLoop {
+        fn on_set(&self, change: &TorqueSubset) {
+            CONTROL_LOOP_COUNT.fetch_add(1, Ordering::SeqCst);
+            assert_eq!(change.torque, 30);
+        }
+    }
+
+    // The fast group is notified while the slow group is being notified, but the slow group
+    // itself is locked
+    static DISPLAY_NOTIFIED: AtomicBool = AtomicBool::new(false);
+    impl DatabaseSubscriber<StatusSubset> for Display<'_> {
+        fn on_set(&self, _change: &StatusSubset) {
+            self.database
+                .notify_group(DriveDatabaseGroup::Fast)
+                .unwrap();
+            assert!(matches!(
+                self.database.notify_group(DriveDatabaseGroup::Slow),
+                Err(DatabaseError::SubscriberLock)
+            ));
+            DISPLAY_NOTIFIED.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let control_loop = ControlLoop {};
+    let display = Display {
+        database: &database,
+    };
+
+    let group_handler = database.get_group_handler();
+    group_handler
+        .subscribe_with_torque_subset(&control_loop)
+        .unwrap();
+    group_handler
+        .subscribe_with_status_subset(&display)
+        .unwrap();
+
+    // A group is notified while the subscriber handler is locked by another context
+    database.set(&DriveDatabaseMember::Torque(30)).unwrap();
+    {
+        let _handler_lock = database.get_subscriber_handler();
+        assert!(matches!(
+            database.notify_subscribers(),
+            Err(DatabaseError::SubscriberLock)
+        ));
+        database.notify_group(DriveDatabaseGroup::Fast).unwrap();
+    }
+    assert_eq!(CONTROL_LOOP_COUNT.load(Ordering::SeqCst), 1);
+
+    database.set(&DriveDatabaseMember::Status(2)).unwrap();
+    database.notify_group(DriveDatabaseGroup::Slow).unwrap();
+    assert!(DISPLAY_NOTIFIED.load(Ordering::SeqCst));
+    assert_eq!(CONTROL_LOOP_COUNT.load(Ordering::SeqCst), 1);
+}
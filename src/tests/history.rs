@@ -0,0 +1,72 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use database_macro::Database;
+
+use crate::Clock;
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(RecordedDatabase)]
+struct RecordedDatabaseContent {
+    alice: u8,
+    #[history(3)]
+    bob: u16,
+}
+
+#[test]
+fn history_keeps_latest_values() {
+    let database = RecordedDatabase::new(RecordedDatabaseContent::default());
+    assert_eq!(database.with_history_of_bob(|history| history.len()), 0);
+
+    database.set(&RecordedDatabaseMember::Bob(1)).unwrap();
+    database
+        .multi_set(&[
+            RecordedDatabaseMember::Alice(1),
+            RecordedDatabaseMember::Bob(2),
+        ])
+        .unwrap();
+    database.set(&RecordedDatabaseMember::Alice(2)).unwrap();
+    database.set(&RecordedDatabaseMember::Bob(3)).unwrap();
+    database.set(&RecordedDatabaseMember::Bob(4)).unwrap();
+
+    // Only the latest values are kept, along with the sequence number of their set operation.
+    // Values aren't timestamped without a clock
+    database.with_history_of_bob(|history| {
+        let mut history = history.iter();
+        assert_eq!(history.next(), Some((1, None, &2)));
+        assert_eq!(history.next(), Some((3, None, &3)));
+        assert_eq!(history.next(), Some((4, None, &4)));
+        assert_eq!(history.next(), None);
+    });
+
+    database.clear_history_of_bob();
+    assert_eq!(database.with_history_of_bob(|history| history.len()), 0);
+}
+
+#[test]
+fn history_is_timestamped_by_clock() {
+    struct TestClock {
+        ticks: AtomicU64,
+    }
+
+    impl Clock for TestClock {
+        fn now(&self) -> u64 {
+            self.ticks.load(Ordering::SeqCst)
+        }
+    }
+
+    let clock = TestClock {
+        ticks: AtomicU64::new(10),
+    };
+    let database = RecordedDatabase::new(RecordedDatabaseContent::default()).with_clock(&clock);
+
+    database.set(&RecordedDatabaseMember::Bob(1)).unwrap();
+    clock.ticks.store(25, Ordering::SeqCst);
+    database.set(&RecordedDatabaseMember::Bob(2)).unwrap();
+
+    database.with_history_of_bob(|history| {
+        let mut history = history.iter();
+        assert_eq!(history.next(), Some((0, Some(10), &1)));
+        assert_eq!(history.next(), Some((1, Some(25), &2)));
+        assert_eq!(history.next(), None);
+    });
+}
@@ -0,0 +1,118 @@
+use core::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::{DatabaseSubscriber, Subset};
+
+fn total_gain(gains: [u16; 4]) -> u32 {
+    gains.iter().map(|gain| *gain as u32).sum()
+}
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(ChannelDatabase)]
+#[subset(FirstChannelSubset)]
+#[subset(AllChannelsSubset)]
+struct ChannelDatabaseContent {
+    enabled: bool,
+    #[indexed]
+    gains: [u16; 4],
+    #[derived(inputs(gains), with = total_gain)]
+    total: u32,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(ChannelDatabase)]
+struct FirstChannelSubset {
+    #[element(gains, 0)]
+    first_gain: u16,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(ChannelDatabase)]
+struct AllChannelsSubset {
+    #[indexed]
+    gains: [u16; 4],
+}
+
+#[test]
+fn indexed_members_are_set_per_element() {
+    let database = ChannelDatabase::new(ChannelDatabaseContent::default());
+    assert_eq!(CHANNEL_DATABASE_MEMBER_COUNT, 6);
+
+    database.set(&ChannelDatabaseMember::Gains(2, 7)).unwrap();
+    database.set(&ChannelDatabaseMember::Gains(3, 5)).unwrap();
+    assert_eq!(
+        database.get(&ChannelDatabaseMember::Gains(2, 0)),
+        ChannelDatabaseMember::Gains(2, 7)
+    );
+    assert_eq!(
+        database.get(&ChannelDatabaseMember::Gains(1, 0)),
+        ChannelDatabaseMember::Gains(1, 0)
+    );
+
+    // Derived members are recomputed when any element of an indexed input changes
+    assert_eq!(
+        database.get(&ChannelDatabaseMember::Total(0)),
+        ChannelDatabaseMember::Total(12)
+    );
+}
+
+#[test]
+fn element_subsets_only_notify_on_their_element() {
+    let database = ChannelDatabase::new(ChannelDatabaseContent::default());
+    struct FirstChannelSubscriber {}
+    struct AllChannelsSubscriber {}
+
+    static FIRST_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static FIRST_GAIN: AtomicU16 = AtomicU16::new(0);
+    static ALL_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static LAST_GAIN: AtomicU16 = AtomicU16::new(0);
+
+    impl DatabaseSubscriber<FirstChannelSubset> for FirstChannelSubscriber {
+        fn on_set(&self, change: &FirstChannelSubset) {
+            FIRST_COUNT.fetch_add(1, Ordering::SeqCst);
+            FIRST_GAIN.store(change.first_gain, Ordering::SeqCst);
+        }
+    }
+
+    impl DatabaseSubscriber<AllChannelsSubset> for AllChannelsSubscriber {
+        fn on_set(&self, change: &AllChannelsSubset) {
+            ALL_COUNT.fetch_add(1, Ordering::SeqCst);
+            LAST_GAIN.store(change.gains[3], Ordering::SeqCst);
+        }
+    }
+
+    let first_subscriber = FirstChannelSubscriber {};
+    let all_subscriber = AllChannelsSubscriber {};
+
+    {
+        let handler_lock = database.get_subscriber_handler();
+        let mut handler = handler_lock.borrow_mut();
+
+        handler
+            .subscribe_with_first_channel_subset(&first_subscriber)
+            .unwrap();
+        handler
+            .subscribe_with_all_channels_subset(&all_subscriber)
+            .unwrap();
+    }
+
+    // Changing another element doesn't notify the subset of the first element
+    database.set(&ChannelDatabaseMember::Gains(3, 9)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(FIRST_COUNT.load(Ordering::SeqCst), 0);
+    assert_eq!(ALL_COUNT.load(Ordering::SeqCst), 1);
+    assert_eq!(LAST_GAIN.load(Ordering::SeqCst), 9);
+
+    database.set(&ChannelDatabaseMember::Gains(0, 4)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(FIRST_COUNT.load(Ordering::SeqCst), 1);
+    assert_eq!(FIRST_GAIN.load(Ordering::SeqCst), 4);
+    assert_eq!(ALL_COUNT.load(Ordering::SeqCst), 2);
+
+    // Members outside of the array notify neither subset
+    database.set(&ChannelDatabaseMember::Enabled(true)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(FIRST_COUNT.load(Ordering::SeqCst), 1);
+    assert_eq!(ALL_COUNT.load(Ordering::SeqCst), 2);
+}
@@ -43,9 +43,7 @@ fn simple_test() {
     struct MySubsetSubscriber {}
 
     static HAS_TRIGGERED: AtomicBool = AtomicBool::new(false);
-    impl DatabaseSubscriber<MyContentSubset1, MyDatabaseMember, MY_DATABASE_MEMBER_COUNT>
-        for MySubsetSubscriber
-    {
+    impl DatabaseSubscriber<MyContentSubset1> for MySubsetSubscriber {
         fn on_set(&self, change: &MyContentSubset1) {
             HAS_TRIGGERED.store(true, Ordering::SeqCst);
             assert_eq!(change.alice, 2);
@@ -57,14 +55,13 @@ fn simple_test() {
 
     database
         .get_subscriber_handler()
-        .lock()
         .borrow_mut()
         .subscribe_with_my_content_subset1(&subscriber)
         .unwrap();
 
     let changes: [MyDatabaseMember; 2] = [MyDatabaseMember::Alice(2), MyDatabaseMember::Bob(144)];
 
-    database.multi_set(&changes);
+    database.multi_set(&changes).unwrap();
     database.notify_subscribers().unwrap();
     assert!(HAS_TRIGGERED.load(Ordering::SeqCst));
 }
@@ -76,7 +73,7 @@ fn dont_notify_non_subscribed() {
     struct MySubsetSubscriber {}
 
     static HAS_TRIGGERED: AtomicBool = AtomicBool::new(false);
-    impl DatabaseSubscriber<MyContentSubset1, MyDatabaseMember, 3> for MySubsetSubscriber {
+    impl DatabaseSubscriber<MyContentSubset1> for MySubsetSubscriber {
         fn on_set(&self, _change: &MyContentSubset1) {
             HAS_TRIGGERED.store(true, Ordering::SeqCst);
         }
@@ -86,12 +83,11 @@ fn dont_notify_non_subscribed() {
 
     database
         .get_subscriber_handler()
-        .lock()
         .borrow_mut()
         .subscribe_with_my_content_subset1(&subscriber)
         .unwrap();
 
-    database.set(&MyDatabaseMember::Bob(2));
+    database.set(&MyDatabaseMember::Bob(2)).unwrap();
     database.notify_subscribers().unwrap();
     assert!(!HAS_TRIGGERED.load(Ordering::SeqCst));
 }
@@ -106,7 +102,7 @@ fn multi_subscriber_test() {
     static HAS_TRIGGERED1: AtomicBool = AtomicBool::new(false);
     static HAS_TRIGGERED2: AtomicBool = AtomicBool::new(false);
 
-    impl DatabaseSubscriber<MyContentSubset1, MyDatabaseMember, 3> for MySubsetSubscriber1 {
+    impl DatabaseSubscriber<MyContentSubset1> for MySubsetSubscriber1 {
         fn on_set(&self, change: &MyContentSubset1) {
             HAS_TRIGGERED1.store(true, Ordering::SeqCst);
             assert_eq!(change.alice, 2);
@@ -114,7 +110,7 @@ fn multi_subscriber_test() {
         }
     }
 
-    impl DatabaseSubscriber<MyContentSubset2, MyDatabaseMember, 3> for MySubsetSubscriber2 {
+    impl DatabaseSubscriber<MyContentSubset2> for MySubsetSubscriber2 {
         fn on_set(&self, change: &MyContentSubset2) {
             HAS_TRIGGERED2.store(true, Ordering::SeqCst);
             assert_eq!(change.debbie, -1);
@@ -125,7 +121,7 @@ fn multi_subscriber_test() {
     let subscriber2 = MySubsetSubscriber2 {};
 
     {
-        let handler_lock = database.get_subscriber_handler().lock();
+        let handler_lock = database.get_subscriber_handler();
         let mut handler = handler_lock.borrow_mut();
 
         handler
@@ -138,7 +134,7 @@ fn multi_subscriber_test() {
 
     let changes: [MyDatabaseMember; 2] = [MyDatabaseMember::Alice(2), MyDatabaseMember::Bob(144)];
 
-    database.multi_set(&changes);
+    database.multi_set(&changes).unwrap();
     database.notify_subscribers().unwrap();
     assert!(HAS_TRIGGERED1.load(Ordering::SeqCst));
     assert!(!HAS_TRIGGERED2.load(Ordering::SeqCst));
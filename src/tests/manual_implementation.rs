@@ -3,7 +3,7 @@ use core::sync::atomic::{AtomicBool, Ordering};
 use crate::{
     Subset,
     content::DatabaseContent,
-    database::{DatabaseHandler, DatabaseRef, ParameterChangeList},
+    database::{DatabaseHandler, DatabaseRef},
     database_error::DatabaseError,
     subscriber_handler::{DatabaseSubscriber, DatabaseSubscriberHandler},
 };
@@ -45,11 +45,9 @@ impl From<MyDatabaseMember> for usize {
 }
 
 struct MySubscriberHandler<'a> {
-    my_content_subset1_subscribers:
-        [Option<&'a dyn DatabaseSubscriber<MyContentSubset1, MyDatabaseMember, 3>>; 128],
+    my_content_subset1_subscribers: [Option<&'a dyn DatabaseSubscriber<MyContentSubset1>>; 128],
 
-    my_content_subset2_subscribers:
-        [Option<&'a dyn DatabaseSubscriber<MyContentSubset2, MyDatabaseMember, 3>>; 128],
+    my_content_subset2_subscribers: [Option<&'a dyn DatabaseSubscriber<MyContentSubset2>>; 128],
 }
 
 impl<'a> MySubscriberHandler<'a> {
@@ -62,7 +60,7 @@ impl<'a> MySubscriberHandler<'a> {
 
     fn subscribe_with_my_content_subset1(
         &mut self,
-        subscriber: &'a dyn DatabaseSubscriber<MyContentSubset1, MyDatabaseMember, 3>,
+        subscriber: &'a dyn DatabaseSubscriber<MyContentSubset1>,
     ) -> Result<(), DatabaseError> {
         for instance in self.my_content_subset1_subscribers.iter_mut() {
             if instance.is_none() {
@@ -75,7 +73,7 @@ impl<'a> MySubscriberHandler<'a> {
 
     fn subscribe_with_my_content_subset2(
         &mut self,
-        subscriber: &'a dyn DatabaseSubscriber<MyContentSubset2, MyDatabaseMember, 3>,
+        subscriber: &'a dyn DatabaseSubscriber<MyContentSubset2>,
     ) -> Result<(), DatabaseError> {
         for instance in self.my_content_subset2_subscribers.iter_mut() {
             if instance.is_none() {
@@ -87,22 +85,18 @@ impl<'a> MySubscriberHandler<'a> {
     }
 }
 
-impl<'a> DatabaseSubscriberHandler<'a, MyDatabaseContent, MyDatabaseMember, 3>
-    for MySubscriberHandler<'a>
-{
+impl<'a> DatabaseSubscriberHandler<'a, MyDatabaseContent> for MySubscriberHandler<'a> {
     fn notify_subscribers(
         &self,
         database: &dyn DatabaseRef<MyDatabaseMember>,
-        parameter_change: &ParameterChangeList<MyDatabaseMember, 3>,
+        parameter_change: &[Option<MyDatabaseMember>],
     ) {
         // MyContentSubset1
         {
             if MyContentSubset1::is_subscribed(parameter_change) {
                 let subset = MyContentSubset1::build_from_database(database);
-                for instance in self.my_content_subset1_subscribers.iter() {
-                    if let Some(instance) = instance {
-                        instance.on_set(&subset);
-                    }
+                for instance in self.my_content_subset1_subscribers.iter().flatten() {
+                    instance.on_set(&subset);
                 }
             }
         }
@@ -110,10 +104,8 @@ impl<'a> DatabaseSubscriberHandler<'a, MyDatabaseContent, MyDatabaseMember, 3>
         {
             if MyContentSubset2::is_subscribed(parameter_change) {
                 let subset = MyContentSubset2::build_from_database(database);
-                for instance in self.my_content_subset2_subscribers.iter() {
-                    if let Some(instance) = instance {
-                        instance.on_set(&subset);
-                    }
+                for instance in self.my_content_subset2_subscribers.iter().flatten() {
+                    instance.on_set(&subset);
                 }
             }
         }
@@ -130,7 +122,11 @@ impl MyDatabaseContent {
     }
 }
 
-impl DatabaseContent<MyDatabaseMember, 3> for MyDatabaseContent {
+impl DatabaseContent for MyDatabaseContent {
+    type Member = MyDatabaseMember;
+
+    const COUNT: usize = 3;
+
     fn set(&mut self, parameter: MyDatabaseMember) {
         match parameter {
             MyDatabaseMember::Alice(value) => self.alice = value,
@@ -148,8 +144,10 @@ impl DatabaseContent<MyDatabaseMember, 3> for MyDatabaseContent {
     }
 }
 
-impl Subset<MyDatabaseMember, 3> for MyContentSubset1 {
-    fn is_subscribed(parameter_change: &ParameterChangeList<MyDatabaseMember, 3>) -> bool {
+impl Subset for MyContentSubset1 {
+    type Database = MyDatabaseContent;
+
+    fn is_subscribed(parameter_change: &[Option<MyDatabaseMember>]) -> bool {
         let alice_index: usize = MyDatabaseMember::Alice(u8::default()).into();
         let debbie_index: usize = MyDatabaseMember::Alice(u8::default()).into();
         parameter_change[alice_index].is_some() || parameter_change[debbie_index].is_some()
@@ -170,8 +168,10 @@ impl Subset<MyDatabaseMember, 3> for MyContentSubset1 {
     }
 }
 
-impl Subset<MyDatabaseMember, 3> for MyContentSubset2 {
-    fn is_subscribed(parameter_change: &ParameterChangeList<MyDatabaseMember, 3>) -> bool {
+impl Subset for MyContentSubset2 {
+    type Database = MyDatabaseContent;
+
+    fn is_subscribed(parameter_change: &[Option<MyDatabaseMember>]) -> bool {
         let debbie_index: usize = MyDatabaseMember::Debbie(isize::default()).into();
         parameter_change[debbie_index].is_some()
     }
@@ -200,7 +200,7 @@ fn simple_test() {
     struct MySubsetSubscriber {}
 
     static HAS_TRIGGERED: AtomicBool = AtomicBool::new(false);
-    impl DatabaseSubscriber<MyContentSubset1, MyDatabaseMember, 3> for MySubsetSubscriber {
+    impl DatabaseSubscriber<MyContentSubset1> for MySubsetSubscriber {
         fn on_set(&self, change: &MyContentSubset1) {
             HAS_TRIGGERED.store(true, Ordering::SeqCst);
             assert_eq!(change.alice, 2);
@@ -212,14 +212,13 @@ fn simple_test() {
 
     database
         .get_subscriber_handler()
-        .lock()
         .borrow_mut()
         .subscribe_with_my_content_subset1(&subscriber)
         .unwrap();
 
     let changes: [MyDatabaseMember; 2] = [MyDatabaseMember::Alice(2), MyDatabaseMember::Bob(144)];
 
-    database.multi_set(&changes);
+    database.multi_set(&changes).unwrap();
     database.notify_subscribers().unwrap();
     assert!(HAS_TRIGGERED.load(Ordering::SeqCst));
 }
@@ -231,7 +230,7 @@ fn dont_notify_non_subscribed() {
     struct MySubsetSubscriber {}
 
     static HAS_TRIGGERED: AtomicBool = AtomicBool::new(false);
-    impl DatabaseSubscriber<MyContentSubset1, MyDatabaseMember, 3> for MySubsetSubscriber {
+    impl DatabaseSubscriber<MyContentSubset1> for MySubsetSubscriber {
         fn on_set(&self, _change: &MyContentSubset1) {
             HAS_TRIGGERED.store(true, Ordering::SeqCst);
         }
@@ -241,12 +240,11 @@ fn dont_notify_non_subscribed() {
 
     database
         .get_subscriber_handler()
-        .lock()
         .borrow_mut()
         .subscribe_with_my_content_subset1(&subscriber)
         .unwrap();
 
-    database.set(&MyDatabaseMember::Bob(2));
+    database.set(&MyDatabaseMember::Bob(2)).unwrap();
     database.notify_subscribers().unwrap();
     assert!(!HAS_TRIGGERED.load(Ordering::SeqCst));
 }
@@ -261,7 +259,7 @@ fn multi_subscriber_test() {
     static HAS_TRIGGERED1: AtomicBool = AtomicBool::new(false);
     static HAS_TRIGGERED2: AtomicBool = AtomicBool::new(false);
 
-    impl DatabaseSubscriber<MyContentSubset1, MyDatabaseMember, 3> for MySubsetSubscriber1 {
+    impl DatabaseSubscriber<MyContentSubset1> for MySubsetSubscriber1 {
         fn on_set(&self, change: &MyContentSubset1) {
             HAS_TRIGGERED1.store(true, Ordering::SeqCst);
             assert_eq!(change.alice, 2);
@@ -269,7 +267,7 @@ fn multi_subscriber_test() {
         }
     }
 
-    impl DatabaseSubscriber<MyContentSubset2, MyDatabaseMember, 3> for MySubsetSubscriber2 {
+    impl DatabaseSubscriber<MyContentSubset2> for MySubsetSubscriber2 {
         fn on_set(&self, change: &MyContentSubset2) {
             HAS_TRIGGERED2.store(true, Ordering::SeqCst);
             assert_eq!(change.debbie, -1);
@@ -280,7 +278,7 @@ fn multi_subscriber_test() {
     let subscriber2 = MySubsetSubscriber2 {};
 
     {
-        let handler_lock = database.get_subscriber_handler().lock();
+        let handler_lock = database.get_subscriber_handler();
         let mut handler = handler_lock.borrow_mut();
 
         handler
@@ -293,7 +291,7 @@ fn multi_subscriber_test() {
 
     let changes: [MyDatabaseMember; 2] = [MyDatabaseMember::Alice(2), MyDatabaseMember::Bob(144)];
 
-    database.multi_set(&changes);
+    database.multi_set(&changes).unwrap();
     database.notify_subscribers().unwrap();
     assert!(HAS_TRIGGERED1.load(Ordering::SeqCst));
     assert!(!HAS_TRIGGERED2.load(Ordering::SeqCst));
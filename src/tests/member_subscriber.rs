@@ -0,0 +1,48 @@
+use core::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::MemberSubscriber;
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(BridgeDatabase)]
+struct BridgeDatabaseContent {
+    alice: u8,
+    bob: u16,
+    debbie: u16,
+}
+
+#[test]
+fn member_subscribers_see_every_change() {
+    let database = BridgeDatabase::new(BridgeDatabaseContent::default());
+    struct Logger {}
+
+    static CHANGE_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static LAST_BOB: AtomicU16 = AtomicU16::new(0);
+    impl MemberSubscriber<BridgeDatabaseMember> for Logger {
+        fn on_member_change(&self, member: &BridgeDatabaseMember) {
+            CHANGE_COUNT.fetch_add(1, Ordering::SeqCst);
+            if let BridgeDatabaseMember::Bob(value) = member {
+                LAST_BOB.store(*value, Ordering::SeqCst);
+            }
+        }
+    }
+
+    let logger = Logger {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_to_members(&logger)
+        .unwrap();
+
+    database
+        .multi_set(&[BridgeDatabaseMember::Alice(1), BridgeDatabaseMember::Bob(2)])
+        .unwrap();
+    database.set(&BridgeDatabaseMember::Bob(7)).unwrap();
+    database.notify_subscribers().unwrap();
+
+    // Each changed member is notified once, with its latest value
+    assert_eq!(CHANGE_COUNT.load(Ordering::SeqCst), 2);
+    assert_eq!(LAST_BOB.load(Ordering::SeqCst), 7);
+}
@@ -1,2 +1,26 @@
+mod budgeted_notify;
+mod change_journal;
+mod change_signal;
+mod deadband;
+mod deferred_notify;
+mod derived;
+mod dynamic_subscriber;
+mod field_subscriber;
+mod flatten;
+mod float;
+mod generics;
+mod groups;
+mod history;
+mod indexed;
 mod macro_implementation;
 mod manual_implementation;
+mod member_subscriber;
+mod module;
+mod non_copy;
+mod notify_policy;
+mod paths;
+mod reader;
+mod static_subscribers;
+mod sync_subscribe;
+mod undo;
+mod versions;
@@ -0,0 +1,88 @@
+use core::sync::atomic::{AtomicU16, Ordering};
+
+use database_macro::Database;
+
+use crate::{DatabaseSubscriber, Subset};
+
+// Two databases in the same module, whose generated items would collide without a module each
+#[derive(Database, Default, Clone, Copy)]
+#[module(left_motor)]
+#[subset(LeftSpeedSubset)]
+struct LeftMotorContent {
+    speed: u16,
+    current: u16,
+}
+
+#[derive(Database, Default, Clone, Copy)]
+#[module(right_motor)]
+struct RightMotorContent {
+    speed: u16,
+    current: u16,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(module = left_motor)]
+struct LeftSpeedSubset {
+    speed: u16,
+}
+
+#[test]
+fn items_are_generated_in_a_module() {
+    let left = left_motor::Database::new(LeftMotorContent::default());
+    let right = right_motor::Database::new(RightMotorContent::default());
+    struct LeftSpeedSubscriber {}
+
+    static LAST_SPEED: AtomicU16 = AtomicU16::new(0);
+    impl DatabaseSubscriber<LeftSpeedSubset> for LeftSpeedSubscriber {
+        fn on_set(&self, change: &LeftSpeedSubset) {
+            LAST_SPEED.store(change.speed, Ordering::SeqCst);
+        }
+    }
+
+    let subscriber = LeftSpeedSubscriber {};
+
+    left.get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_left_speed_subset(&subscriber)
+        .unwrap();
+
+    left.set(&left_motor::Member::Speed(300)).unwrap();
+    right.set(&right_motor::Member::Speed(200)).unwrap();
+    left.notify_subscribers().unwrap();
+    assert_eq!(LAST_SPEED.load(Ordering::SeqCst), 300);
+    assert_eq!(
+        right.get(&right_motor::Member::Speed(0)),
+        right_motor::Member::Speed(200)
+    );
+
+    // Keys identify members without their values
+    assert_eq!(
+        left_motor::Member::Current(4).key(),
+        left_motor::Key::Current
+    );
+    assert_ne!(left_motor::Member::Speed(4).key(), left_motor::Key::Current);
+    assert_eq!(left_motor::COUNT, 2);
+}
+
+// The items of a database in a module are generated in the module, and not in its parent
+mod items {
+    #[allow(dead_code)]
+    struct LeftMotorMember;
+    #[allow(dead_code)]
+    struct LeftMotorContentSubscriberHandler;
+    #[allow(dead_code)]
+    struct LeftMotorDatabase;
+
+    #[derive(database_macro::Database, Default, Clone, Copy)]
+    #[module(left_motor)]
+    struct LeftMotorContent {
+        speed: u16,
+    }
+
+    #[test]
+    fn items_are_not_generated_in_the_parent() {
+        let database = left_motor::Database::new(LeftMotorContent::default());
+        database.set(&left_motor::Member::Speed(1)).unwrap();
+        let _handler: left_motor::SubscriberHandler = left_motor::SubscriberHandler::new();
+    }
+}
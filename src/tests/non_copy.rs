@@ -0,0 +1,90 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::{DatabaseSubscriber, Subset};
+
+// A bounded string, which is `Clone` but not `Copy`
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DeviceName {
+    bytes: [u8; 16],
+    len: usize,
+}
+
+impl DeviceName {
+    fn new(name: &str) -> Self {
+        let mut bytes = [0; 16];
+        bytes[..name.len()].copy_from_slice(name.as_bytes());
+        Self {
+            bytes,
+            len: name.len(),
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+    }
+}
+
+// Members that aren't all `Copy` opt out of `Copy` for the member enum
+#[derive(Database, Default)]
+#[name(DeviceDatabase)]
+#[no_copy]
+#[subset(DeviceSubset)]
+struct DeviceDatabaseContent {
+    #[history(2)]
+    name: DeviceName,
+    channel: u8,
+}
+
+#[derive(Subset, Clone)]
+#[superset(DeviceDatabase)]
+struct DeviceSubset {
+    name: DeviceName,
+}
+
+#[test]
+fn clone_only_members() {
+    let database = DeviceDatabase::new(DeviceDatabaseContent::default());
+    struct DeviceSubscriber {}
+
+    static TRIGGER_COUNT: AtomicUsize = AtomicUsize::new(0);
+    impl DatabaseSubscriber<DeviceSubset> for DeviceSubscriber {
+        fn on_set(&self, change: &DeviceSubset) {
+            TRIGGER_COUNT.fetch_add(1, Ordering::SeqCst);
+            assert_eq!(change.name.as_str(), "pump");
+        }
+    }
+
+    let subscriber = DeviceSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_device_subset(&subscriber)
+        .unwrap();
+
+    database
+        .set(&DeviceDatabaseMember::Name(DeviceName::new("pump")))
+        .unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+
+    // Setting the same value again isn't a change
+    database
+        .set(&DeviceDatabaseMember::Name(DeviceName::new("pump")))
+        .unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+
+    // Members can be borrowed without being copied out of the database
+    assert_eq!(database.with_name(|name| name.len), 4);
+    assert!(database.with_name(|name| name.as_str() == "pump"));
+    assert_eq!(database.with_channel(|channel| *channel), 0);
+
+    // The history of members that aren't `Copy` is borrowed as well, and records every set
+    database.with_history_of_name(|history| {
+        assert_eq!(history.len(), 2);
+        assert!(history.iter().all(|(_, _, name)| name.as_str() == "pump"));
+    });
+}
@@ -0,0 +1,248 @@
+use core::sync::atomic::{AtomicU16, AtomicU64, AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::{Clock, DatabaseSubscriber, NotifyPolicy, Subset};
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(TimedDatabase)]
+#[subset(BobSubset)]
+struct TimedDatabaseContent {
+    alice: u8,
+    bob: u16,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(TimedDatabase)]
+struct BobSubset {
+    bob: u16,
+}
+
+#[test]
+fn rate_limited_subscriber() {
+    let database = TimedDatabase::new(TimedDatabaseContent::default());
+    struct BobSubscriber {}
+
+    static TRIGGER_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static LAST_BOB: AtomicU16 = AtomicU16::new(0);
+    impl DatabaseSubscriber<BobSubset> for BobSubscriber {
+        fn on_set(&self, change: &BobSubset) {
+            TRIGGER_COUNT.fetch_add(1, Ordering::SeqCst);
+            LAST_BOB.store(change.bob, Ordering::SeqCst);
+        }
+    }
+
+    let subscriber = BobSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_bob_subset_policy(&subscriber, NotifyPolicy::RateLimit(100))
+        .unwrap();
+
+    // Rate limited subscribers are only notified when polled
+    database.set(&TimedDatabaseMember::Bob(1)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 0);
+    database.poll(0u64).unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+
+    // Changes within the rate limit are held back until the interval has passed
+    database.set(&TimedDatabaseMember::Bob(2)).unwrap();
+    database.notify_subscribers().unwrap();
+    database.poll(50u64).unwrap();
+    database.set(&TimedDatabaseMember::Bob(3)).unwrap();
+    database.notify_subscribers().unwrap();
+    database.poll(99u64).unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+
+    database.poll(100u64).unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 2);
+    assert_eq!(LAST_BOB.load(Ordering::SeqCst), 3);
+
+    // Nothing is notified without pending changes
+    database.poll(300u64).unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn debounced_subscriber() {
+    let database = TimedDatabase::new(TimedDatabaseContent::default());
+    struct BobSubscriber {}
+
+    static TRIGGER_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static LAST_BOB: AtomicU16 = AtomicU16::new(0);
+    impl DatabaseSubscriber<BobSubset> for BobSubscriber {
+        fn on_set(&self, change: &BobSubset) {
+            TRIGGER_COUNT.fetch_add(1, Ordering::SeqCst);
+            LAST_BOB.store(change.bob, Ordering::SeqCst);
+        }
+    }
+
+    let subscriber = BobSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_bob_subset_policy(&subscriber, NotifyPolicy::Debounce(50))
+        .unwrap();
+
+    // Every change restarts the stable period
+    for (now, value) in [(0u32, 1), (20, 2), (40, 3)] {
+        database.set(&TimedDatabaseMember::Bob(value)).unwrap();
+        database.notify_subscribers().unwrap();
+        database.poll(now).unwrap();
+    }
+    database.poll(89u32).unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 0);
+
+    database.poll(90u32).unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+    assert_eq!(LAST_BOB.load(Ordering::SeqCst), 3);
+}
+
+struct TestClock {
+    ticks: AtomicU64,
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> u64 {
+        self.ticks.load(Ordering::SeqCst)
+    }
+}
+
+#[test]
+fn rate_limited_subscriber_with_clock() {
+    let clock = TestClock {
+        ticks: AtomicU64::new(1000),
+    };
+    let database = TimedDatabase::new(TimedDatabaseContent::default()).with_clock(&clock);
+    struct BobSubscriber {}
+
+    static TRIGGER_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static LAST_BOB: AtomicU16 = AtomicU16::new(0);
+    impl DatabaseSubscriber<BobSubset> for BobSubscriber {
+        fn on_set(&self, change: &BobSubset) {
+            TRIGGER_COUNT.fetch_add(1, Ordering::SeqCst);
+            LAST_BOB.store(change.bob, Ordering::SeqCst);
+        }
+    }
+
+    let subscriber = BobSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_bob_subset_policy(&subscriber, NotifyPolicy::RateLimit(100))
+        .unwrap();
+
+    // The first change is notified directly, without waiting for a poll
+    database.set(&TimedDatabaseMember::Bob(1)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+
+    // Changes within the rate limit are held back until polled after the interval
+    clock.ticks.store(1050, Ordering::SeqCst);
+    database.set(&TimedDatabaseMember::Bob(2)).unwrap();
+    database.notify_subscribers().unwrap();
+    database.poll(1099u64).unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+    database.poll(1100u64).unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 2);
+    assert_eq!(LAST_BOB.load(Ordering::SeqCst), 2);
+
+    // The interval is measured from the last notification
+    clock.ticks.store(1150, Ordering::SeqCst);
+    database.set(&TimedDatabaseMember::Bob(3)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 2);
+    clock.ticks.store(1200, Ordering::SeqCst);
+    database.set(&TimedDatabaseMember::Bob(4)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 3);
+    assert_eq!(LAST_BOB.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn debounced_subscriber_with_clock() {
+    let clock = TestClock {
+        ticks: AtomicU64::new(0),
+    };
+    let database = TimedDatabase::new(TimedDatabaseContent::default()).with_clock(&clock);
+    struct BobSubscriber {}
+
+    static TRIGGER_COUNT: AtomicUsize = AtomicUsize::new(0);
+    impl DatabaseSubscriber<BobSubset> for BobSubscriber {
+        fn on_set(&self, _change: &BobSubset) {
+            TRIGGER_COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let subscriber = BobSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_bob_subset_policy(&subscriber, NotifyPolicy::Debounce(50))
+        .unwrap();
+
+    // The stable period is measured from the change, not from the first poll after it
+    clock.ticks.store(10, Ordering::SeqCst);
+    database.set(&TimedDatabaseMember::Bob(1)).unwrap();
+    database.notify_subscribers().unwrap();
+    database.poll(59u64).unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 0);
+    database.poll(60u64).unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+}
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(FilteredDatabase)]
+#[subset(FilteredSubset)]
+struct FilteredDatabaseContent {
+    level: u16,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(FilteredDatabase)]
+#[deadband(level = 10)]
+struct FilteredSubset {
+    level: u16,
+}
+
+#[test]
+fn filtered_notification_does_not_count_towards_rate_limit() {
+    let database = FilteredDatabase::new(FilteredDatabaseContent::default());
+    struct LevelSubscriber {}
+
+    static TRIGGER_COUNT: AtomicUsize = AtomicUsize::new(0);
+    impl DatabaseSubscriber<FilteredSubset> for LevelSubscriber {
+        fn on_set(&self, _change: &FilteredSubset) {
+            TRIGGER_COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let subscriber = LevelSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_filtered_subset_policy(&subscriber, NotifyPolicy::RateLimit(100))
+        .unwrap();
+
+    database.set(&FilteredDatabaseMember::Level(100)).unwrap();
+    database.notify_subscribers().unwrap();
+    database.poll(0u64).unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+
+    // A change within the deadband is dropped, and doesn't restart the rate limit
+    database.set(&FilteredDatabaseMember::Level(105)).unwrap();
+    database.notify_subscribers().unwrap();
+    database.poll(150u64).unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+
+    database.set(&FilteredDatabaseMember::Level(120)).unwrap();
+    database.notify_subscribers().unwrap();
+    database.poll(160u64).unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,50 @@
+use core::sync::atomic::{AtomicU16, Ordering};
+
+use crate::DatabaseSubscriber;
+
+mod db {
+    use database_macro::Database;
+
+    #[derive(Database, Default, Clone, Copy)]
+    #[name(PumpDatabase)]
+    #[subset(super::subsets::PumpSubset)]
+    pub struct PumpDatabaseContent {
+        pub flow: u16,
+        pub pressure: u16,
+    }
+}
+
+mod subsets {
+    use database_macro::Subset;
+
+    #[derive(Subset, Clone, Copy)]
+    #[superset(crate::tests::paths::db::PumpDatabase)]
+    pub struct PumpSubset {
+        pub flow: u16,
+    }
+}
+
+#[test]
+fn subsets_and_databases_in_separate_modules() {
+    let database = db::PumpDatabase::new(db::PumpDatabaseContent::default());
+    struct PumpSubscriber {}
+
+    static LAST_FLOW: AtomicU16 = AtomicU16::new(0);
+    impl DatabaseSubscriber<subsets::PumpSubset> for PumpSubscriber {
+        fn on_set(&self, change: &subsets::PumpSubset) {
+            LAST_FLOW.store(change.flow, Ordering::SeqCst);
+        }
+    }
+
+    let subscriber = PumpSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_pump_subset(&subscriber)
+        .unwrap();
+
+    database.set(&db::PumpDatabaseMember::Flow(12)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(LAST_FLOW.load(Ordering::SeqCst), 12);
+}
@@ -0,0 +1,45 @@
+use database_macro::{Database, Subset};
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(ThermostatDatabase)]
+struct ThermostatDatabaseContent {
+    temperature: i16,
+    setpoint: i16,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(ThermostatDatabase)]
+struct TemperatureSubset {
+    temperature: i16,
+}
+
+#[test]
+fn readers_poll_independently() {
+    let database = ThermostatDatabase::new(ThermostatDatabaseContent::default());
+    let mut display = database.reader::<TemperatureSubset>();
+    let mut logger = database.reader::<TemperatureSubset>();
+
+    // Readers only see changes made after they were created
+    assert!(display.poll().is_none());
+
+    database
+        .set(&ThermostatDatabaseMember::Temperature(21))
+        .unwrap();
+    assert_eq!(display.poll().map(|subset| subset.temperature), Some(21));
+    assert!(display.poll().is_none());
+
+    // Notifying subscribers doesn't consume the changes seen by readers
+    database.notify_subscribers().unwrap();
+    database
+        .set(&ThermostatDatabaseMember::Temperature(22))
+        .unwrap();
+    assert_eq!(logger.poll().map(|subset| subset.temperature), Some(22));
+    assert_eq!(display.poll().map(|subset| subset.temperature), Some(22));
+
+    // Changes outside of the subset aren't read
+    database
+        .set(&ThermostatDatabaseMember::Setpoint(19))
+        .unwrap();
+    assert!(display.poll().is_none());
+    assert!(logger.poll().is_none());
+}
@@ -0,0 +1,170 @@
+use core::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::{DatabaseSubscriber, StaticSubset, Subset};
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(DisplayDatabase)]
+#[subset(BrightnessSubset, subscribers(BACKLIGHT, STATUS_LED))]
+#[subset(ContrastSubset)]
+struct DisplayDatabaseContent {
+    brightness: u16,
+    contrast: u16,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(DisplayDatabase)]
+#[deadband(brightness = 10)]
+struct BrightnessSubset {
+    brightness: u16,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(DisplayDatabase)]
+struct ContrastSubset {
+    contrast: u16,
+}
+
+static BACKLIGHT_LEVEL: AtomicU16 = AtomicU16::new(0);
+static STATUS_LED_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+struct Backlight {}
+
+impl DatabaseSubscriber<BrightnessSubset> for Backlight {
+    fn on_set(&self, change: &BrightnessSubset) {
+        BACKLIGHT_LEVEL.store(change.brightness, Ordering::SeqCst);
+    }
+}
+
+struct StatusLed {}
+
+impl DatabaseSubscriber<BrightnessSubset> for StatusLed {
+    fn on_set(&self, _change: &BrightnessSubset) {
+        STATUS_LED_COUNT.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+static BACKLIGHT: Backlight = Backlight {};
+static STATUS_LED: StatusLed = StatusLed {};
+
+#[test]
+fn static_subscribers_are_notified_without_registration() {
+    let database = DisplayDatabase::new(DisplayDatabaseContent::default());
+
+    database
+        .set(&DisplayDatabaseMember::Brightness(100))
+        .unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(BACKLIGHT_LEVEL.load(Ordering::SeqCst), 100);
+    assert_eq!(STATUS_LED_COUNT.load(Ordering::SeqCst), 1);
+
+    // Static subscribers are filtered by the deadband of their subset
+    database
+        .set(&DisplayDatabaseMember::Brightness(105))
+        .unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(BACKLIGHT_LEVEL.load(Ordering::SeqCst), 100);
+    assert_eq!(STATUS_LED_COUNT.load(Ordering::SeqCst), 1);
+
+    database
+        .set(&DisplayDatabaseMember::Brightness(120))
+        .unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(BACKLIGHT_LEVEL.load(Ordering::SeqCst), 120);
+    assert_eq!(STATUS_LED_COUNT.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn static_and_dynamic_subsets_are_mixed() {
+    let database = DisplayDatabase::new(DisplayDatabaseContent::default());
+    struct ContrastSubscriber {}
+
+    static LAST_CONTRAST: AtomicU16 = AtomicU16::new(0);
+    impl DatabaseSubscriber<ContrastSubset> for ContrastSubscriber {
+        fn on_set(&self, change: &ContrastSubset) {
+            LAST_CONTRAST.store(change.contrast, Ordering::SeqCst);
+        }
+    }
+
+    let subscriber = ContrastSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_contrast_subset(&subscriber)
+        .unwrap();
+
+    database.set(&DisplayDatabaseMember::Contrast(7)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(LAST_CONTRAST.load(Ordering::SeqCst), 7);
+}
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(PanelDatabase)]
+#[subset(DimmingSubset, subscribers(DIMMER))]
+struct PanelDatabaseContent {
+    dimming: u16,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(PanelDatabase)]
+struct DimmingSubset {
+    dimming: u16,
+}
+
+static DIMMER_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+struct Dimmer {}
+
+impl DatabaseSubscriber<DimmingSubset> for Dimmer {
+    fn on_set(&self, change: &DimmingSubset) {
+        assert_eq!(change.dimming, 200);
+        DIMMER_COUNT.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+static DIMMER: Dimmer = Dimmer {};
+
+#[test]
+fn static_subscribers_are_followed_by_registered_subscribers() {
+    let database = PanelDatabase::new(PanelDatabaseContent::default());
+    struct DimmingSubscriber {}
+
+    static DIMMING_COUNT: AtomicUsize = AtomicUsize::new(0);
+    impl DatabaseSubscriber<DimmingSubset> for DimmingSubscriber {
+        fn on_set(&self, _change: &DimmingSubset) {
+            // The static subscriber is notified before any registered subscriber
+            assert_eq!(
+                DIMMER_COUNT.load(Ordering::SeqCst),
+                DIMMING_COUNT.load(Ordering::SeqCst) + 1
+            );
+            DIMMING_COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let subscriber = DimmingSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_dimming_subset(&subscriber)
+        .unwrap();
+
+    database.set(&PanelDatabaseMember::Dimming(200)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(DIMMER_COUNT.load(Ordering::SeqCst), 1);
+    assert_eq!(DIMMING_COUNT.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn unfiltered_subsets_store_nothing_for_static_subscribers() {
+    assert_eq!(
+        core::mem::size_of::<<DimmingSubset as StaticSubset>::LastNotified>(),
+        0
+    );
+    assert_ne!(
+        core::mem::size_of::<<BrightnessSubset as StaticSubset>::LastNotified>(),
+        0
+    );
+}
@@ -0,0 +1,80 @@
+use core::sync::atomic::{AtomicU8, AtomicU16, AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::{DatabaseSubscriber, Subset};
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(DisplayDatabase)]
+#[subset(DisplaySubset)]
+#[subset(BadgeSubset, group = slow)]
+struct DisplayDatabaseContent {
+    alice: u8,
+    bob: u16,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(DisplayDatabase)]
+struct DisplaySubset {
+    bob: u16,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(DisplayDatabase)]
+struct BadgeSubset {
+    alice: u8,
+}
+
+#[test]
+fn synced_subscribers_are_notified_on_subscribe() {
+    let database = DisplayDatabase::new(DisplayDatabaseContent { alice: 0, bob: 12 });
+    struct Display {}
+
+    static CALL_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static LAST_BOB: AtomicU16 = AtomicU16::new(0);
+    impl DatabaseSubscriber<DisplaySubset> for Display {
+        fn on_set(&self, change: &DisplaySubset) {
+            CALL_COUNT.fetch_add(1, Ordering::SeqCst);
+            LAST_BOB.store(change.bob, Ordering::SeqCst);
+        }
+    }
+
+    let display = Display {};
+
+    // The current value is notified before anything is set
+    database
+        .subscribe_with_display_subset_and_sync(&display)
+        .unwrap();
+    assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 1);
+    assert_eq!(LAST_BOB.load(Ordering::SeqCst), 12);
+
+    database.set(&DisplayDatabaseMember::Bob(13)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(CALL_COUNT.load(Ordering::SeqCst), 2);
+    assert_eq!(LAST_BOB.load(Ordering::SeqCst), 13);
+}
+
+#[test]
+fn synced_group_subscribers_are_notified_on_subscribe() {
+    let database = DisplayDatabase::new(DisplayDatabaseContent { alice: 7, bob: 0 });
+    struct Badge {}
+
+    static LAST_ALICE: AtomicU8 = AtomicU8::new(0);
+    impl DatabaseSubscriber<BadgeSubset> for Badge {
+        fn on_set(&self, change: &BadgeSubset) {
+            LAST_ALICE.store(change.alice, Ordering::SeqCst);
+        }
+    }
+
+    let badge = Badge {};
+
+    // Subsets in groups are synced through the handler of their group
+    database
+        .subscribe_with_badge_subset_and_sync(&badge)
+        .unwrap();
+    assert_eq!(LAST_ALICE.load(Ordering::SeqCst), 7);
+
+    database.set(&DisplayDatabaseMember::Alice(8)).unwrap();
+    database.notify_group(DisplayDatabaseGroup::Slow).unwrap();
+    assert_eq!(LAST_ALICE.load(Ordering::SeqCst), 8);
+}
@@ -0,0 +1,81 @@
+use core::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
+
+use database_macro::Database;
+
+use crate::{DatabaseSubscriber, Subset, UndoJournal};
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(ConfigDatabase)]
+#[subset(BobSubset)]
+struct ConfigDatabaseContent {
+    alice: u8,
+    bob: u16,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(ConfigDatabase)]
+struct BobSubset {
+    bob: u16,
+}
+
+#[test]
+fn undo_and_redo_set_operations() {
+    let journal: UndoJournal<ConfigDatabaseMember, CONFIG_DATABASE_MEMBER_COUNT, 2> =
+        UndoJournal::new();
+    let database =
+        ConfigDatabase::new(ConfigDatabaseContent::default()).with_undo_journal(&journal);
+    struct BobSubscriber {}
+
+    static TRIGGER_COUNT: AtomicUsize = AtomicUsize::new(0);
+    static LAST_BOB: AtomicU16 = AtomicU16::new(0);
+    impl DatabaseSubscriber<BobSubset> for BobSubscriber {
+        fn on_set(&self, change: &BobSubset) {
+            TRIGGER_COUNT.fetch_add(1, Ordering::SeqCst);
+            LAST_BOB.store(change.bob, Ordering::SeqCst);
+        }
+    }
+
+    let subscriber = BobSubscriber {};
+
+    database
+        .get_subscriber_handler()
+        .borrow_mut()
+        .subscribe_with_bob_subset(&subscriber)
+        .unwrap();
+
+    database.set(&ConfigDatabaseMember::Bob(1)).unwrap();
+    database
+        .multi_set(&[ConfigDatabaseMember::Alice(1), ConfigDatabaseMember::Bob(2)])
+        .unwrap();
+    database.set(&ConfigDatabaseMember::Bob(3)).unwrap();
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 1);
+
+    // Restored values are notified as any other change
+    assert!(database.undo());
+    database.notify_subscribers().unwrap();
+    assert_eq!(TRIGGER_COUNT.load(Ordering::SeqCst), 2);
+    assert_eq!(LAST_BOB.load(Ordering::SeqCst), 2);
+
+    // The whole batch is undone at once, and the depth of the journal is bounded
+    assert!(database.undo());
+    assert_eq!(
+        database.get(&ConfigDatabaseMember::Alice(0)),
+        ConfigDatabaseMember::Alice(0)
+    );
+    assert_eq!(
+        database.get(&ConfigDatabaseMember::Bob(0)),
+        ConfigDatabaseMember::Bob(1)
+    );
+    assert!(!database.undo());
+
+    assert!(database.redo());
+    assert_eq!(
+        database.get(&ConfigDatabaseMember::Bob(0)),
+        ConfigDatabaseMember::Bob(2)
+    );
+
+    // A new set operation discards everything that could be redone
+    database.set(&ConfigDatabaseMember::Alice(4)).unwrap();
+    assert!(!database.redo());
+}
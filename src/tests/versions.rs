@@ -0,0 +1,95 @@
+use database_macro::{Database, Subset};
+
+fn average(levels: [u16; 2]) -> u16 {
+    (levels[0] + levels[1]) / 2
+}
+
+#[derive(Database, Default, Clone, Copy)]
+#[name(TelemetryDatabase)]
+struct TelemetryDatabaseContent {
+    mode: u8,
+    #[indexed]
+    levels: [u16; 2],
+    #[derived(inputs(levels), with = average)]
+    average_level: u16,
+}
+
+#[derive(Subset, Clone, Copy)]
+#[superset(TelemetryDatabase)]
+struct ModeSubset {
+    mode: u8,
+}
+
+#[test]
+fn changed_since_version() {
+    let database = TelemetryDatabase::new(TelemetryDatabaseContent::default());
+    let start = database.version();
+
+    database
+        .multi_set(&[
+            TelemetryDatabaseMember::Levels(1, 10),
+            TelemetryDatabaseMember::Mode(0),
+        ])
+        .unwrap();
+    let after_levels = database.version();
+    assert_eq!(after_levels, start + 1);
+
+    // Parameters set to their current value aren't changed, and derived parameters are
+    let mut changed = database.changed_since(start);
+    assert_eq!(changed.next(), Some(TelemetryDatabaseKey::Levels));
+    assert_eq!(changed.next(), Some(TelemetryDatabaseKey::AverageLevel));
+    assert_eq!(changed.next(), None);
+
+    // Operations without any changes don't increase the version
+    database.set(&TelemetryDatabaseMember::Mode(0)).unwrap();
+    assert_eq!(database.version(), after_levels);
+
+    database.set(&TelemetryDatabaseMember::Mode(2)).unwrap();
+    let mut changed = database.changed_since(after_levels);
+    assert_eq!(changed.next(), Some(TelemetryDatabaseKey::Mode));
+    assert_eq!(changed.next(), None);
+    assert_eq!(database.changed_since(database.version()).count(), 0);
+}
+
+#[test]
+fn readers_keep_their_own_cursors() {
+    let database = TelemetryDatabase::new(TelemetryDatabaseContent::default());
+    let mut first_cursor = database.version();
+    let mut second_cursor = database.version();
+
+    database.set(&TelemetryDatabaseMember::Mode(1)).unwrap();
+
+    let now = database.version();
+    let subset = database.subset_changed_since::<ModeSubset>(first_cursor);
+    assert_eq!(subset.map(|subset| subset.mode), Some(1));
+    first_cursor = now;
+    assert!(
+        database
+            .subset_changed_since::<ModeSubset>(first_cursor)
+            .is_none()
+    );
+
+    // Changes to other members don't build the subset
+    database
+        .set(&TelemetryDatabaseMember::Levels(0, 4))
+        .unwrap();
+    assert!(
+        database
+            .subset_changed_since::<ModeSubset>(first_cursor)
+            .is_none()
+    );
+
+    // The second reader hasn't looked yet, and still sees the change
+    let now = database.version();
+    assert!(
+        database
+            .subset_changed_since::<ModeSubset>(second_cursor)
+            .is_some()
+    );
+    second_cursor = now;
+    assert!(
+        database
+            .subset_changed_since::<ModeSubset>(second_cursor)
+            .is_none()
+    );
+}
@@ -0,0 +1,124 @@
+use critical_section::{CriticalSection, Mutex as CriticalMutex};
+
+use crate::database::{ParameterChangeList, RefCell};
+
+/// A single set operation recorded in an undo journal. Contains the values of all parameters set
+/// in the operation, both before and after it was applied
+#[derive(Debug, Clone)]
+pub struct JournalEntry<Parameter, const PARAMETER_COUNT: usize>
+where
+    Parameter: Clone + PartialEq,
+{
+    pub(crate) previous: ParameterChangeList<Parameter, PARAMETER_COUNT>,
+    pub(crate) current: ParameterChangeList<Parameter, PARAMETER_COUNT>,
+}
+
+/// An `UndoLog` keeps track of set operations that can be undone and redone. This is used by a
+/// database to be able to keep a journal of a size that isn't part of the database type itself.
+/// All operations are made in the critical section of the database
+pub trait UndoLog<Parameter, const PARAMETER_COUNT: usize>
+where
+    Parameter: Clone + PartialEq,
+{
+    /// Record a new set operation. This discards all operations that can be redone
+    fn record(&self, cs: CriticalSection, entry: JournalEntry<Parameter, PARAMETER_COUNT>);
+
+    /// Take the latest operation to undo, and make it available to be redone
+    fn take_undo(&self, cs: CriticalSection) -> Option<JournalEntry<Parameter, PARAMETER_COUNT>>;
+
+    /// Take the latest undone operation to redo, and make it available to be undone again
+    fn take_redo(&self, cs: CriticalSection) -> Option<JournalEntry<Parameter, PARAMETER_COUNT>>;
+}
+
+/// An `UndoJournal` is a statically sized journal of the latest `DEPTH` set operations made to a
+/// database. When the journal is full, the oldest operation is discarded
+pub struct UndoJournal<Parameter, const PARAMETER_COUNT: usize, const DEPTH: usize>
+where
+    Parameter: Clone + PartialEq,
+{
+    internal: CriticalMutex<RefCell<InternalUndoJournal<Parameter, PARAMETER_COUNT, DEPTH>>>,
+}
+
+// The entries are kept in a ring buffer, where the operations to undo are the ones before the
+// cursor and the operations to redo are the ones from the cursor and onwards
+struct InternalUndoJournal<Parameter, const PARAMETER_COUNT: usize, const DEPTH: usize>
+where
+    Parameter: Clone + PartialEq,
+{
+    entries: [Option<JournalEntry<Parameter, PARAMETER_COUNT>>; DEPTH],
+    cursor: usize,
+    undo_len: usize,
+    redo_len: usize,
+}
+
+impl<Parameter, const PARAMETER_COUNT: usize, const DEPTH: usize>
+    UndoJournal<Parameter, PARAMETER_COUNT, DEPTH>
+where
+    Parameter: Clone + PartialEq,
+{
+    /// Create an empty journal
+    pub const fn new() -> Self {
+        Self {
+            internal: CriticalMutex::new(RefCell::new(InternalUndoJournal {
+                entries: [const { None }; DEPTH],
+                cursor: 0,
+                undo_len: 0,
+                redo_len: 0,
+            })),
+        }
+    }
+}
+
+impl<Parameter, const PARAMETER_COUNT: usize, const DEPTH: usize> Default
+    for UndoJournal<Parameter, PARAMETER_COUNT, DEPTH>
+where
+    Parameter: Clone + PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Parameter, const PARAMETER_COUNT: usize, const DEPTH: usize>
+    UndoLog<Parameter, PARAMETER_COUNT> for UndoJournal<Parameter, PARAMETER_COUNT, DEPTH>
+where
+    Parameter: Clone + PartialEq,
+{
+    fn record(&self, cs: CriticalSection, entry: JournalEntry<Parameter, PARAMETER_COUNT>) {
+        if DEPTH == 0 {
+            return;
+        }
+
+        let mut internal = self.internal.borrow(cs).borrow_mut();
+        let cursor = internal.cursor;
+        let _ = internal.entries[cursor].insert(entry);
+        internal.cursor = (cursor + 1) % DEPTH;
+        internal.undo_len = (internal.undo_len + 1).min(DEPTH);
+        internal.redo_len = 0;
+    }
+
+    fn take_undo(&self, cs: CriticalSection) -> Option<JournalEntry<Parameter, PARAMETER_COUNT>> {
+        let mut internal = self.internal.borrow(cs).borrow_mut();
+        if internal.undo_len == 0 {
+            return None;
+        }
+
+        internal.cursor = (internal.cursor + DEPTH - 1) % DEPTH;
+        internal.undo_len -= 1;
+        internal.redo_len += 1;
+        internal.entries[internal.cursor].clone()
+    }
+
+    fn take_redo(&self, cs: CriticalSection) -> Option<JournalEntry<Parameter, PARAMETER_COUNT>> {
+        let mut internal = self.internal.borrow(cs).borrow_mut();
+        if internal.redo_len == 0 {
+            return None;
+        }
+
+        let entry = internal.entries[internal.cursor].clone();
+        internal.cursor = (internal.cursor + 1) % DEPTH;
+        internal.redo_len -= 1;
+        internal.undo_len += 1;
+        entry
+    }
+}